@@ -0,0 +1,322 @@
+use crate::artwork::ArtworkImage;
+#[cfg(feature = "async")]
+use crate::async_client::AsyncAppleMusicClient;
+use crate::calls;
+use crate::client::AppleMusicClient;
+use crate::commands;
+use crate::editor::BulkTrackEditor;
+use crate::error::{Error, ScriptError};
+use crate::jxa::Jxa;
+use crate::partial_track::{PartialTrack, TrackField};
+use crate::query::{Filter, Operator, TrackQuery};
+use crate::selection::TrackResult;
+use crate::track::Track;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Album of the library, grouping every Track sharing the same album name & album artist.
+///
+/// Album properties are stored on each of its Tracks by Apple Music, and are changed on all of them at once:
+/// ```rust,ignore
+/// let album = client.album("Discovery", "Daft Punk");
+///
+/// album.set_favorited(true)?;
+/// album.set_rating(100)?;
+/// ```
+/// Changing an Album without any Track, e.g. with a misspelled name, returns an `Error::ObjectNotFound`.
+#[derive(Debug, Clone)]
+pub struct Album<C = AppleMusicClient> {
+    client: C,
+    name: String,
+    artist: String,
+}
+
+impl<C> Album<C> {
+    pub(crate) fn new(client: C, name: &str, artist: &str) -> Self {
+        Album {
+            client,
+            name: name.into(),
+            artist: artist.into(),
+        }
+    }
+
+    /// Returns the name of the Album.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the album artist of the Album.
+    pub fn artist(&self) -> &str {
+        &self.artist
+    }
+
+    /// Returns the Filter matching every Track of the Album.
+    pub fn to_filter(&self) -> Filter<TrackField> {
+        Filter::Condition(
+            TrackField::Album,
+            Operator::Equals,
+            Value::from(&*self.name),
+        )
+        .and(Filter::Condition(
+            TrackField::AlbumArtist,
+            Operator::Equals,
+            Value::from(&*self.artist),
+        ))
+    }
+
+    /// Returns the first failure of the edit of every Track of the Album, if any,
+    /// or an `Error::ObjectNotFound` if the Album has no Track at all, e.g. a misspelled name.
+    fn first_failure(&self, script: &str, results: Vec<TrackResult>) -> Result<(), Error> {
+        if results.is_empty() {
+            return Err(ScriptError {
+                code: Some(ScriptError::OBJECT_NOT_FOUND),
+                message: format!("No track of album {:?} by {:?}", self.name, self.artist),
+                exit_code: None,
+                script: script.into(),
+                params: None,
+            }
+            .into());
+        }
+
+        results
+            .into_iter()
+            .find_map(|edit| edit.result.err())
+            .map_or(Ok(()), Err)
+    }
+}
+
+impl Album<AppleMusicClient> {
+    /// Returns a new query on the Tracks of the Album.
+    pub fn tracks(&self) -> TrackQuery<&AppleMusicClient> {
+        self.client.tracks().matching(self.to_filter())
+    }
+
+    /// Fetches and returns every Track of the Album.
+    pub fn fetch_tracks(&self) -> Result<Vec<Track>, Error> {
+        self.tracks().execute()
+    }
+
+    /// Favorites / "Unfavorites" the Album.
+    pub fn set_favorited(&self, value: bool) -> Result<(), Error> {
+        self.apply(self.edit().album_favorited(value))
+    }
+
+    /// Dislikes / "Undislikes" the Album.
+    pub fn set_disliked(&self, value: bool) -> Result<(), Error> {
+        self.apply(self.edit().album_disliked(value))
+    }
+
+    /// Sets the rating of the Album, from 0 to 100.
+    pub fn set_rating(&self, value: i32) -> Result<(), Error> {
+        self.apply(self.edit().album_rating(value))
+    }
+
+    /// Replaces the first artwork of every Track of the Album with the provided image.
+    /// Returns an `Error::InvalidArgument` if the image is not a PNG, JPEG or TIFF image.
+    pub fn replace_artwork(&self, image: &ArtworkImage) -> Result<(), Error> {
+        self.client.call(calls::replace_artwork(
+            &self.client.app(),
+            &self.matches(),
+            image,
+        ))
+    }
+
+    /// Deletes every artwork of every Track of the Album.
+    pub fn remove_artworks(&self) -> Result<(), Error> {
+        self.client
+            .execute(&commands::remove_artworks(&self.matches()))
+    }
+
+    fn matches(&self) -> Jxa {
+        Jxa::raw(&format!(
+            "{}()",
+            self.tracks().specifier(&self.client.app())
+        ))
+    }
+
+    fn edit(&self) -> BulkTrackEditor {
+        self.tracks().edit()
+    }
+
+    fn apply(&self, editor: BulkTrackEditor) -> Result<(), Error> {
+        let script = editor.to_script()?;
+        self.first_failure(&script, editor.apply()?)
+    }
+}
+
+#[cfg(feature = "async")]
+impl Album<AsyncAppleMusicClient> {
+    /// Returns a new query on the Tracks of the Album.
+    pub fn tracks(&self) -> TrackQuery<&AsyncAppleMusicClient> {
+        self.client.tracks().matching(self.to_filter())
+    }
+
+    /// Fetches and returns every Track of the Album.
+    pub async fn fetch_tracks(&self) -> Result<Vec<Track>, Error> {
+        self.tracks().execute().await
+    }
+
+    /// Favorites / "Unfavorites" the Album.
+    pub async fn set_favorited(&self, value: bool) -> Result<(), Error> {
+        self.apply(self.edit().album_favorited(value)).await
+    }
+
+    /// Dislikes / "Undislikes" the Album.
+    pub async fn set_disliked(&self, value: bool) -> Result<(), Error> {
+        self.apply(self.edit().album_disliked(value)).await
+    }
+
+    /// Sets the rating of the Album, from 0 to 100.
+    pub async fn set_rating(&self, value: i32) -> Result<(), Error> {
+        self.apply(self.edit().album_rating(value)).await
+    }
+
+    /// Replaces the first artwork of every Track of the Album with the provided image.
+    /// Returns an `Error::InvalidArgument` if the image is not a PNG, JPEG or TIFF image.
+    pub async fn replace_artwork(&self, image: &ArtworkImage) -> Result<(), Error> {
+        self.client
+            .call(calls::replace_artwork(
+                &self.client.app(),
+                &self.matches(),
+                image,
+            ))
+            .await
+    }
+
+    /// Deletes every artwork of every Track of the Album.
+    pub async fn remove_artworks(&self) -> Result<(), Error> {
+        self.client
+            .execute(&commands::remove_artworks(&self.matches()))
+            .await
+    }
+
+    fn matches(&self) -> Jxa {
+        Jxa::raw(&format!(
+            "{}()",
+            self.tracks().specifier(&self.client.app())
+        ))
+    }
+
+    fn edit(&self) -> BulkTrackEditor<AsyncAppleMusicClient> {
+        self.tracks().edit()
+    }
+
+    async fn apply(&self, editor: BulkTrackEditor<AsyncAppleMusicClient>) -> Result<(), Error> {
+        let script = editor.to_script()?;
+        self.first_failure(&script, editor.apply().await?)
+    }
+}
+
+/// Fields fetched to group Library Tracks into Albums.
+pub(crate) const ALBUM_FIELDS: [TrackField; 2] = [TrackField::Album, TrackField::AlbumArtist];
+
+/// Returns the Albums of the provided Tracks, in order of first appearance. Tracks without album are skipped.
+pub(crate) fn group_albums<C: Clone>(client: &C, tracks: Vec<PartialTrack>) -> Vec<Album<C>> {
+    let mut seen = HashSet::new();
+
+    tracks
+        .into_iter()
+        .filter_map(|track| {
+            let name = track.album.unwrap_or_default();
+            let artist = track.album_artist.unwrap_or_default();
+
+            if name.is_empty() || !seen.insert((name.clone(), artist.clone())) {
+                return None;
+            }
+
+            Some(Album::new(client.clone(), &name, &artist))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::id::TrackId;
+    use serde_json::json;
+
+    fn album() -> Album<()> {
+        Album::new((), "Discovery", "Daft Punk")
+    }
+
+    fn result(id: i32, result: Result<(), Error>) -> TrackResult {
+        TrackResult {
+            track: TrackId::Id(id),
+            result,
+        }
+    }
+
+    fn track(album: Option<&str>, artist: Option<&str>) -> PartialTrack {
+        serde_json::from_value(json!({"album": album, "albumArtist": artist})).unwrap()
+    }
+
+    fn names(albums: &[Album<()>]) -> Vec<(&str, &str)> {
+        albums
+            .iter()
+            .map(|album| (album.name(), album.artist()))
+            .collect()
+    }
+
+    #[test]
+    fn fails_without_any_track() {
+        match album().first_failure("script", Vec::new()) {
+            Err(Error::ObjectNotFound(err)) => {
+                assert_eq!(err.code, Some(ScriptError::OBJECT_NOT_FOUND));
+                assert_eq!(err.script, "script");
+            }
+            result => panic!("expected ObjectNotFound, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn returns_first_failure() {
+        let results = vec![
+            result(1, Ok(())),
+            result(2, Err(Error::Cancelled)),
+            result(3, Err(Error::NotPlaying)),
+        ];
+
+        assert!(matches!(
+            album().first_failure("script", results),
+            Err(Error::Cancelled)
+        ));
+        assert!(album()
+            .first_failure("script", vec![result(1, Ok(())), result(2, Ok(()))])
+            .is_ok());
+    }
+
+    #[test]
+    fn groups_albums_in_order_of_first_appearance() {
+        let tracks = vec![
+            track(Some("Discovery"), Some("Daft Punk")),
+            track(Some("Random Access Memories"), Some("Daft Punk")),
+            track(Some("Discovery"), Some("Daft Punk")),
+            track(Some("Discovery"), Some("Various Artists")),
+            track(Some("Homework"), None),
+        ];
+
+        assert_eq!(
+            names(&group_albums(&(), tracks)),
+            [
+                ("Discovery", "Daft Punk"),
+                ("Random Access Memories", "Daft Punk"),
+                ("Discovery", "Various Artists"),
+                ("Homework", ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_tracks_without_album() {
+        let tracks = vec![
+            track(None, Some("Daft Punk")),
+            track(Some(""), Some("Daft Punk")),
+            track(Some("Alive 2007"), Some("Daft Punk")),
+        ];
+
+        assert_eq!(
+            names(&group_albums(&(), tracks)),
+            [("Alive 2007", "Daft Punk")]
+        );
+    }
+}
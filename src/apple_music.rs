@@ -1,232 +1,239 @@
+use crate::album::Album;
 use crate::application_data::ApplicationData;
+use crate::client::AppleMusicClient;
 use crate::error::Error;
+use crate::handle::{PlaylistRef, TrackRef};
+use crate::id::{PlaylistId, TrackId};
+use crate::paging::TrackPages;
+use crate::partial_track::{PartialTrack, TrackField};
 use crate::playlist::Playlist;
-use crate::script_controller::{ParamType, ScriptController};
-use crate::track::Track;
+use crate::search::{SearchKind, SearchTarget};
+use crate::selection::TrackResult;
+use crate::track::{DeadTrack, Track};
+use std::path::Path;
 use strum_macros::Display;
 
 /// Strict entry point of the module containing the whole logic.
+///
+/// Every function is a thin wrapper over a default `AppleMusicClient`.
 pub struct AppleMusic;
 
 impl AppleMusic {
     /// Returns an up-to-date ApplicationData struct.
     pub fn get_application_data() -> Result<ApplicationData, Error> {
-        match ScriptController.execute_script::<ApplicationData>(
-            ParamType::ApplicationData,
-            None,
-            None,
-        ) {
-            Ok(data) => Ok(data),
-            Err(err) => Err(err),
-        }
+        AppleMusicClient::default().get_application_data()
+    }
+
+    /// Returns a lightweight handle on the provided Track, running commands without fetching it.
+    pub fn track<T: Into<TrackId>>(track: T) -> TrackRef {
+        AppleMusicClient::default().track(track)
+    }
+
+    /// Returns a lightweight handle on the provided Playlist, running commands without fetching it.
+    pub fn playlist<P: Into<PlaylistId>>(playlist: P) -> PlaylistRef {
+        AppleMusicClient::default().playlist(playlist)
+    }
+
+    /// Returns the Album with the provided name & album artist, running commands on all of its Tracks.
+    pub fn album(name: &str, artist: &str) -> Album {
+        AppleMusicClient::default().album(name, artist)
+    }
+
+    /// Returns every Album of the library, grouping Library Tracks by album name & album artist.
+    pub fn get_albums() -> Result<Vec<Album>, Error> {
+        AppleMusicClient::default().get_albums()
+    }
+
+    /// Adds the provided files & folders to the library, or to the provided Playlist, and returns the added Tracks.
+    pub fn add<P: AsRef<Path>>(
+        paths: &[P],
+        to_playlist: Option<PlaylistId>,
+    ) -> Result<Vec<Track>, Error> {
+        AppleMusicClient::default().add(paths, to_playlist)
+    }
+
+    /// Updates every provided file Track from the metadata of its file, in a single script, and returns their results.
+    pub fn refresh_tracks<I, T>(tracks: I) -> Result<Vec<TrackResult>, Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<TrackId>,
+    {
+        AppleMusicClient::default().refresh_tracks(tracks)
+    }
+
+    /// Scans every file Track of the library in a single script, and returns those whose file does not exist anymore.
+    pub fn find_dead_tracks() -> Result<Vec<DeadTrack>, Error> {
+        AppleMusicClient::default().find_dead_tracks()
     }
 
     /// Looks for and returns a Playlist based on provided id, if it exists.
     pub fn get_playlist_by_id(id: i32) -> Result<Playlist, Error> {
-        match ScriptController.execute_script::<Playlist>(ParamType::PlaylistById, Some(id), None) {
-            Ok(data) => Ok(data),
-            Err(err) => Err(err),
-        }
+        AppleMusicClient::default().get_playlist_by_id(id)
+    }
+
+    /// Looks for and returns a Playlist based on provided persistent ID, if it exists.
+    pub fn get_playlist_by_persistent_id(persistent_id: &str) -> Result<Playlist, Error> {
+        AppleMusicClient::default().get_playlist_by_persistent_id(persistent_id)
+    }
+
+    /// Looks for and returns a Track based on provided persistent ID, if it exists.
+    pub fn get_track_by_persistent_id(persistent_id: &str) -> Result<Track, Error> {
+        AppleMusicClient::default().get_track_by_persistent_id(persistent_id)
+    }
+
+    /// Looks for and returns the Tracks with the provided ids, in a single script.
+    /// Results are in the same order as `ids`, with `None` for every Track which does not exist.
+    pub fn get_tracks_by_ids(ids: &[i32]) -> Result<Vec<Option<Track>>, Error> {
+        AppleMusicClient::default().get_tracks_by_ids(ids)
+    }
+
+    /// Looks for and returns the Tracks with the provided persistent IDs, in a single script.
+    /// Results are in the same order as `persistent_ids`, with `None` for every Track which does not exist.
+    pub fn get_tracks_by_persistent_ids<S: AsRef<str>>(
+        persistent_ids: &[S],
+    ) -> Result<Vec<Option<Track>>, Error> {
+        AppleMusicClient::default().get_tracks_by_persistent_ids(persistent_ids)
     }
 
     /// Returns currently playing Track, if any.
     pub fn get_current_track() -> Result<Track, Error> {
-        match ScriptController.execute_script::<Track>(ParamType::CurrentTrack, None, None) {
-            Ok(data) => Ok(data),
-            Err(err) => Err(err),
-        }
+        AppleMusicClient::default().get_current_track()
     }
 
-    /// Fetches and returns a list of all Library Tracks.
-    /// WARNING: Might fail if more than 900 Tracks are to be returned, due to a JavaScript limit.
+    /// Fetches and returns a list of all Library Tracks, `DEFAULT_PAGE_SIZE` Tracks per script.
     pub fn get_all_library_tracks() -> Result<Vec<Track>, Error> {
-        match ScriptController.execute_script::<Vec<Track>>(ParamType::AllTracks, None, None) {
-            Ok(data) => Ok(data),
-            Err(err) => Err(err),
-        }
+        AppleMusicClient::default().get_all_library_tracks()
     }
 
-    /// Plays the provided Track on AppleMusic player.
-    pub fn play_track(track: &Track) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').play(Application('Music').tracks.byId({}))",
-            track.id
-        );
+    /// Returns an iterator fetching Library Tracks page by page, `page_size` Tracks per script.
+    pub fn library_track_pages(page_size: usize) -> TrackPages {
+        AppleMusicClient::default().library_track_pages(page_size)
+    }
 
-        let _ = ScriptController.execute(cmd.as_str(), None);
+    /// Fetches and returns all Library Tracks, only holding the provided fields.
+    pub fn get_all_library_partial_tracks(
+        fields: &[TrackField],
+    ) -> Result<Vec<PartialTrack>, Error> {
+        AppleMusicClient::default().get_all_library_partial_tracks(fields)
+    }
 
-        Ok(())
+    /// Returns the number of Library Tracks, without fetching them.
+    pub fn count_library_tracks() -> Result<usize, Error> {
+        AppleMusicClient::default().count_library_tracks()
     }
 
-    /// Plays the provided Playlist on AppleMusic player.
-    pub fn play_playlist(playlist: &Playlist) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').play(Application('Music').playlists.byId({}))",
-            playlist.id
-        );
+    /// Returns true if a Playlist with the provided id exists, without fetching it.
+    pub fn playlist_exists(id: i32) -> Result<bool, Error> {
+        AppleMusicClient::default().playlist_exists(id)
+    }
 
-        let _ = ScriptController.execute(cmd.as_str(), None);
+    /// Searches the whole library for Tracks matching provided query, as Apple Music's search field does.
+    pub fn search(query: &str, kind: SearchKind) -> Result<Vec<Track>, Error> {
+        AppleMusicClient::default().search(query, kind)
+    }
 
-        Ok(())
+    /// Searches inside provided Playlist or source for Tracks matching provided query.
+    pub fn search_in<S: Into<SearchTarget>>(
+        target: S,
+        query: &str,
+        kind: SearchKind,
+    ) -> Result<Vec<Track>, Error> {
+        AppleMusicClient::default().search_in(target, query, kind)
     }
 
-    /// Mutes / Unmutes AppleMusic player.
-    pub fn set_mute(value: bool) -> Result<(), Error> {
-        let cmd = format!("Application('Music').mute = {}", value);
+    /// Plays the provided Track on AppleMusic player.
+    pub fn play_track<T: Into<TrackId>>(track: T) -> Result<(), Error> {
+        AppleMusicClient::default().play_track(track)
+    }
 
-        let _ = ScriptController.execute(cmd.as_str(), None);
+    /// Plays the provided Playlist on AppleMusic player.
+    pub fn play_playlist<P: Into<PlaylistId>>(playlist: P) -> Result<(), Error> {
+        AppleMusicClient::default().play_playlist(playlist)
+    }
 
-        Ok(())
+    /// Mutes / Unmutes AppleMusic player.
+    pub fn set_mute(value: bool) -> Result<(), Error> {
+        AppleMusicClient::default().set_mute(value)
     }
 
     /// Activates / Deactivates Shuffle mode on AppleMusic player.
     pub fn set_shuffle(value: bool) -> Result<(), Error> {
-        let cmd = format!("Application('Music').shuffleEnabled = {}", value);
-
-        let _ = ScriptController.execute(cmd.as_str(), None);
-
-        Ok(())
+        AppleMusicClient::default().set_shuffle(value)
     }
 
     /// Sets Song Repeat mode to provided value.
     pub fn set_song_repeat_mode(value: SongRepeatMode) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').songRepeat = \"{}\"",
-            value.to_string()
-        );
-
-        let _ = ScriptController.execute(cmd.as_str(), None);
-
-        Ok(())
+        AppleMusicClient::default().set_song_repeat_mode(value)
     }
 
     /// Sets Sound Volume to provided value. ( 0 <= value <= 100 )
     pub fn set_sound_volume(value: i8) -> Result<(), Error> {
-        let cmd = format!("Application('Music').soundVolume = {}", value);
-
-        let _ = ScriptController.execute(cmd.as_str(), None);
-
-        Ok(())
+        AppleMusicClient::default().set_sound_volume(value)
     }
 
     /// Tries to convert the provided Track.
-    pub fn convert_track(track: &Track) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').convert(Application('Music').tracks.byId({}))",
-            track.id
-        );
-
-        let _ = ScriptController.execute(cmd.as_str(), None);
-
-        Ok(())
+    pub fn convert_track<T: Into<TrackId>>(track: T) -> Result<(), Error> {
+        AppleMusicClient::default().convert_track(track)
     }
 
     /// Resumes the player if a track is Paused, otherwise Plays a Track from Library.
     pub fn play() -> Result<(), Error> {
-        let cmd = "Application('Music').play()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().play()
     }
 
     /// Pauses the player's currently playing Track.
     pub fn pause() -> Result<(), Error> {
-        let cmd = "Application('Music').pause()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().pause()
     }
 
     /// Stops Rewinding / Fast-Forwarding and plays the Track at normal speed.
     pub fn resume() -> Result<(), Error> {
-        let cmd = "Application('Music').resume()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().resume()
     }
 
     /// Restart the current Track.
     pub fn back_track() -> Result<(), Error> {
-        let cmd = "Application('Music').backTrack()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().back_track()
     }
 
     /// Fast-forwards the current Track up until resuming or end of current Track.
     pub fn fast_forward() -> Result<(), Error> {
-        let cmd = "Application('Music').fastForward()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().fast_forward()
     }
 
     /// Skips current Track and plays next one.
     pub fn next_track() -> Result<(), Error> {
-        let cmd = "Application('Music').nextTrack()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().next_track()
     }
 
     /// Plays if Player is currently Paused, Pauses if Player is currently Playing.
     pub fn playpause() -> Result<(), Error> {
-        let cmd = "Application('Music').playpause()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().playpause()
     }
 
     /// Goes back to previous Track and plays it.
     pub fn previous_track() -> Result<(), Error> {
-        let cmd = "Application('Music').previousTrack()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().previous_track()
     }
 
     /// Rewinds current Track up until resuming or start of Track.
     pub fn rewind() -> Result<(), Error> {
-        let cmd = "Application('Music').rewind()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().rewind()
     }
 
     /// Stops player, removing enqueued Tracks and currently playing Track.
     pub fn stop() -> Result<(), Error> {
-        let cmd = "Application('Music').stop()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().stop()
     }
 
     /// Fully Quits Apple Music.
     pub fn quit() -> Result<(), Error> {
-        let cmd = "Application('Music').quit()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().quit()
     }
 
     /// Opens Apple Music app.
     pub fn run() -> Result<(), Error> {
-        let cmd = "Application('Music').run()";
-
-        let _ = ScriptController.execute(cmd, None);
-
-        Ok(())
+        AppleMusicClient::default().run()
     }
 }
 
@@ -0,0 +1,421 @@
+use crate::error::Error;
+use crate::track::Artwork;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::{env, fs, process};
+use strum_macros::Display;
+
+/// Format of an image, detected from its first bytes.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Tiff,
+}
+
+impl ImageFormat {
+    /// Detects the format of the provided image from its magic bytes, if supported.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(ImageFormat::Png),
+            [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+            [b'I', b'I', 0x2A, 0x00, ..] | [b'M', b'M', 0x00, 0x2A, ..] => Some(ImageFormat::Tiff),
+            _ => None,
+        }
+    }
+
+    /// Returns the usual file extension of the format, without leading dot.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Tiff => "tiff",
+        }
+    }
+
+    /// Returns the MIME type of the format, e.g. `image/png`.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "image/png",
+            ImageFormat::Jpeg => "image/jpeg",
+            ImageFormat::Tiff => "image/tiff",
+        }
+    }
+
+    /// Reads the width & height of the provided image from its header, if possible.
+    fn dimensions(&self, bytes: &[u8]) -> Option<(u32, u32)> {
+        match self {
+            ImageFormat::Png => Some((read_u32(bytes, 16, true)?, read_u32(bytes, 20, true)?)),
+            ImageFormat::Jpeg => jpeg_dimensions(bytes),
+            ImageFormat::Tiff => tiff_dimensions(bytes),
+        }
+    }
+}
+
+/// Decoded image of an Artwork.
+///
+/// e.g. exporting every artwork of a Track:
+/// ```rust,ignore
+/// for (index, artwork) in track.fetch_artworks()?.iter().enumerate() {
+///     let image = artwork.image()?;
+///     println!("{:?} {:?}", image.format, image.dimensions);
+///     image.write_in("covers", &format!("{}-{}", track.persistent_id, index))?;
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtworkImage {
+    /// Bytes of the image, in its original format
+    pub data: Vec<u8>,
+
+    /// Format of the image, if supported
+    pub format: Option<ImageFormat>,
+
+    /// Width & height of the image in pixels, if they could be read from its header
+    pub dimensions: Option<(u32, u32)>,
+}
+
+impl ArtworkImage {
+    /// Reads and decodes the image at the provided path.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        Ok(ArtworkImage::new(fs::read(path)?))
+    }
+
+    /// Decodes the image held by the provided bytes.
+    pub fn new(data: Vec<u8>) -> Self {
+        let format = ImageFormat::detect(&data);
+        let dimensions = format.and_then(|format| format.dimensions(&data));
+
+        ArtworkImage {
+            data,
+            format,
+            dimensions,
+        }
+    }
+
+    /// Writes the image to the provided path, as is.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        Ok(fs::write(path, &self.data)?)
+    }
+
+    /// Writes the image inside the provided directory, named after `name` and the extension of its format.
+    /// Returns the path of the written file.
+    pub fn write_in<P: AsRef<Path>>(&self, directory: P, name: &str) -> Result<PathBuf, Error> {
+        let mut path = directory.as_ref().join(name);
+
+        if let Some(format) = self.format {
+            path.set_extension(format.extension());
+        }
+
+        self.write_to(&path)?;
+        Ok(path)
+    }
+
+    /// Returns the format of the image, or an `Error::InvalidArgument` if it cannot be used as artwork.
+    fn validate(&self) -> Result<ImageFormat, Error> {
+        self.format.ok_or_else(|| {
+            Error::InvalidArgument(String::from(
+                "artwork image must be a PNG, JPEG or TIFF image",
+            ))
+        })
+    }
+}
+
+/// Copy of an artwork image in the temporary directory, read from there by Apple Music.
+/// The file is removed once dropped.
+pub(crate) struct TempImage(PathBuf);
+
+impl TempImage {
+    /// Validates the format of the image, then writes it to a new temporary file.
+    pub(crate) fn new(image: &ArtworkImage) -> Result<Self, Error> {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let format = image.validate()?;
+        let name = format!(
+            "apple-music-artwork-{}-{}",
+            process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        );
+
+        let path = env::temp_dir()
+            .join(name)
+            .with_extension(format.extension());
+        image.write_to(&path)?;
+
+        Ok(TempImage(path))
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempImage {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+impl Artwork {
+    /// Returns the bytes of the Artwork, in its original format.
+    pub fn bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok(STANDARD.decode(&self.raw_data)?)
+    }
+
+    /// Returns the decoded image of the Artwork, with its format & dimensions.
+    pub fn image(&self) -> Result<ArtworkImage, Error> {
+        Ok(ArtworkImage::new(self.bytes()?))
+    }
+
+    /// Writes the image of the Artwork to the provided path, as is.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), Error> {
+        Ok(fs::write(path, self.bytes()?)?)
+    }
+}
+
+/// Returns the size of the first frame of a JPEG image, from its Start Of Frame segment.
+fn jpeg_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let mut position = 2;
+
+    loop {
+        if *bytes.get(position)? != 0xFF {
+            return None;
+        }
+
+        let marker = *bytes.get(position + 1)?;
+        position += 2;
+
+        match marker {
+            // Fill byte
+            0xFF => position -= 1,
+            // Segments without length
+            0x01 | 0xD0..=0xD8 => {}
+            // End Of Image
+            0xD9 => return None,
+            // Start Of Frame, except DHT, JPG & DAC
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                let height = read_u16(bytes, position + 3, true)?;
+                let width = read_u16(bytes, position + 5, true)?;
+                return Some((width.into(), height.into()));
+            }
+            _ => position += usize::from(read_u16(bytes, position, true)?),
+        }
+    }
+}
+
+/// Returns the size of the first image of a TIFF file, from its first Image File Directory.
+fn tiff_dimensions(bytes: &[u8]) -> Option<(u32, u32)> {
+    let big_endian = bytes.starts_with(b"MM");
+    let directory = usize::try_from(read_u32(bytes, 4, big_endian)?).ok()?;
+    let entries = read_u16(bytes, directory, big_endian)?;
+
+    let (mut width, mut height) = (None, None);
+
+    for index in 0..usize::from(entries) {
+        let entry = directory + 2 + index * 12;
+        let value = match read_u16(bytes, entry + 2, big_endian)? {
+            // SHORT
+            3 => u32::from(read_u16(bytes, entry + 8, big_endian)?),
+            // LONG
+            4 => read_u32(bytes, entry + 8, big_endian)?,
+            _ => continue,
+        };
+
+        match read_u16(bytes, entry, big_endian)? {
+            256 => width = Some(value),
+            257 => height = Some(value),
+            _ => {}
+        }
+    }
+
+    Some((width?, height?))
+}
+
+fn read_u16(bytes: &[u8], position: usize, big_endian: bool) -> Option<u16> {
+    let bytes = bytes.get(position..position + 2)?.try_into().ok()?;
+
+    Some(if big_endian {
+        u16::from_be_bytes(bytes)
+    } else {
+        u16::from_le_bytes(bytes)
+    })
+}
+
+fn read_u32(bytes: &[u8], position: usize, big_endian: bool) -> Option<u32> {
+    let bytes = bytes.get(position..position + 4)?.try_into().ok()?;
+
+    Some(if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn png(width: u32, height: u32) -> Vec<u8> {
+        let mut bytes = vec![0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+        bytes.extend(13u32.to_be_bytes());
+        bytes.extend(b"IHDR");
+        bytes.extend(width.to_be_bytes());
+        bytes.extend(height.to_be_bytes());
+        bytes.extend([8, 6, 0, 0, 0]);
+        bytes
+    }
+
+    fn jpeg(frame_marker: u8, width: u16, height: u16) -> Vec<u8> {
+        let mut bytes = vec![0xFF, 0xD8];
+        // APP0 (JFIF)
+        bytes.extend([0xFF, 0xE0, 0x00, 0x10]);
+        bytes.extend(b"JFIF\0");
+        bytes.extend([0x01, 0x01, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00]);
+        // APP1 (Exif), preceded by a fill byte
+        bytes.extend([0xFF, 0xFF, 0xE1, 0x00, 0x08]);
+        bytes.extend(b"Exif\0\0");
+        // DHT, which shares the range of Start Of Frame markers
+        bytes.extend([0xFF, 0xC4, 0x00, 0x03, 0x00]);
+        // Start Of Frame
+        bytes.extend([0xFF, frame_marker, 0x00, 0x11, 0x08]);
+        bytes.extend(height.to_be_bytes());
+        bytes.extend(width.to_be_bytes());
+        bytes.extend([0x03, 0x01, 0x22, 0x00, 0x02, 0x11, 0x01, 0x03, 0x11, 0x01]);
+        bytes.extend([0xFF, 0xD9]);
+        bytes
+    }
+
+    fn tiff(big_endian: bool, width: u32, height: u16) -> Vec<u8> {
+        let u16_bytes = |value: u16| match big_endian {
+            true => value.to_be_bytes(),
+            false => value.to_le_bytes(),
+        };
+        let u32_bytes = |value: u32| match big_endian {
+            true => value.to_be_bytes(),
+            false => value.to_le_bytes(),
+        };
+
+        let mut bytes = match big_endian {
+            true => b"MM\x00\x2A".to_vec(),
+            false => b"II\x2A\x00".to_vec(),
+        };
+        bytes.extend(u32_bytes(8));
+        bytes.extend(u16_bytes(3));
+        // BitsPerSample, SHORT
+        bytes.extend(u16_bytes(258));
+        bytes.extend(u16_bytes(3));
+        bytes.extend(u32_bytes(1));
+        bytes.extend(u16_bytes(8));
+        bytes.extend([0, 0]);
+        // ImageWidth, LONG
+        bytes.extend(u16_bytes(256));
+        bytes.extend(u16_bytes(4));
+        bytes.extend(u32_bytes(1));
+        bytes.extend(u32_bytes(width));
+        // ImageLength, SHORT
+        bytes.extend(u16_bytes(257));
+        bytes.extend(u16_bytes(3));
+        bytes.extend(u32_bytes(1));
+        bytes.extend(u16_bytes(height));
+        bytes.extend([0, 0]);
+        bytes.extend(u32_bytes(0));
+        bytes
+    }
+
+    #[test]
+    fn reads_png() {
+        let image = ArtworkImage::new(png(600, 400));
+
+        assert_eq!(image.format, Some(ImageFormat::Png));
+        assert_eq!(image.dimensions, Some((600, 400)));
+    }
+
+    #[test]
+    fn reads_baseline_jpeg() {
+        let image = ArtworkImage::new(jpeg(0xC0, 1200, 1000));
+
+        assert_eq!(image.format, Some(ImageFormat::Jpeg));
+        assert_eq!(image.dimensions, Some((1200, 1000)));
+    }
+
+    #[test]
+    fn reads_progressive_jpeg() {
+        let image = ArtworkImage::new(jpeg(0xC2, 300, 3000));
+
+        assert_eq!(image.format, Some(ImageFormat::Jpeg));
+        assert_eq!(image.dimensions, Some((300, 3000)));
+    }
+
+    #[test]
+    fn reads_little_endian_tiff() {
+        let image = ArtworkImage::new(tiff(false, 70000, 512));
+
+        assert_eq!(image.format, Some(ImageFormat::Tiff));
+        assert_eq!(image.dimensions, Some((70000, 512)));
+    }
+
+    #[test]
+    fn reads_big_endian_tiff() {
+        let image = ArtworkImage::new(tiff(true, 70000, 512));
+
+        assert_eq!(image.format, Some(ImageFormat::Tiff));
+        assert_eq!(image.dimensions, Some((70000, 512)));
+    }
+
+    #[test]
+    fn rejects_unknown_format() {
+        for bytes in [&b""[..], b"GIF89a", b"<svg></svg>", &[0xFF, 0xD8], &[0; 64]] {
+            let image = ArtworkImage::new(bytes.to_vec());
+
+            assert_eq!(image.format, None);
+            assert_eq!(image.dimensions, None);
+            assert!(image.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn handles_truncated_images() {
+        let images = [
+            png(600, 400),
+            jpeg(0xC0, 1200, 1000),
+            jpeg(0xC2, 300, 3000),
+            tiff(false, 70000, 512),
+            tiff(true, 70000, 512),
+        ];
+
+        for bytes in &images {
+            let format = ImageFormat::detect(bytes).unwrap();
+            let dimensions = format.dimensions(bytes);
+
+            for length in 0..bytes.len() {
+                let truncated = ArtworkImage::new(bytes[..length].to_vec());
+
+                if length < 16 {
+                    assert_eq!(truncated.dimensions, None);
+                } else {
+                    // Either the header is missing, or it is complete
+                    assert!(truncated.dimensions.is_none() || truncated.dimensions == dimensions);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn handles_garbage_headers() {
+        // JPEG segment longer than the image
+        assert_eq!(jpeg_dimensions(&[0xFF, 0xD8, 0xFF, 0xE0, 0xFF, 0xFF]), None);
+        // JPEG segment not starting with a marker
+        assert_eq!(jpeg_dimensions(&[0xFF, 0xD8, 0x00, 0xC0, 0x00, 0x11]), None);
+        // JPEG ending before any frame
+        assert_eq!(jpeg_dimensions(&[0xFF, 0xD8, 0xFF, 0xD9]), None);
+        // TIFF directory out of bounds
+        assert_eq!(tiff_dimensions(b"II\x2A\x00\xFF\xFF\xFF\xFF"), None);
+        // TIFF directory with more entries than bytes
+        assert_eq!(tiff_dimensions(b"MM\x00\x2A\x00\x00\x00\x08\xFF\xFF"), None);
+        // TIFF without dimensions
+        let mut bytes = tiff(false, 1, 1);
+        bytes[8..10].copy_from_slice(&1u16.to_le_bytes());
+        assert_eq!(tiff_dimensions(&bytes), None);
+    }
+}
@@ -0,0 +1,839 @@
+use crate::album::{group_albums, Album, ALBUM_FIELDS};
+use crate::apple_music::SongRepeatMode;
+use crate::application_data::ApplicationData;
+use crate::artwork::ArtworkImage;
+use crate::calls::{self, Call, ClientConfig};
+use crate::commands;
+use crate::editor::{BulkTrackEditor, TrackEditor};
+use crate::error::{Error, ScriptError};
+use crate::handle::{PlaylistRef, TrackRef};
+use crate::id::{PlaylistId, TrackId};
+use crate::jxa::Jxa;
+use crate::paging::{PageCursor, TrackPage, DEFAULT_PAGE_SIZE};
+use crate::partial_track::{PartialTrack, TrackField};
+use crate::playlist::Playlist;
+use crate::query::{PlaylistQuery, TrackQuery};
+use crate::script_controller::{
+    CancellationToken, ExecutionOptions, ScriptRequest, POLL_INTERVAL, SCRIPT,
+};
+use crate::search::{SearchKind, SearchTarget};
+use crate::selection::{TrackResult, TrackSelection};
+use crate::track::{Artwork, DeadTrack, Track};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+
+/// Boxed future returned by `AsyncScriptBackend` methods.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// Async counterpart of `ScriptBackend`, used by `AsyncAppleMusicClient`.
+///
+/// `AsyncScriptController` is the default implementation, relying on `osascript` through `tokio::process`.
+pub trait AsyncScriptBackend: Send + Sync {
+    /// Executes a raw JXA script, with optional parameters, and returns its output.
+    fn execute<'a>(
+        &'a self,
+        script: &'a str,
+        params: Option<&'a str>,
+        options: &'a ExecutionOptions,
+    ) -> BoxFuture<'a, Result<String, Error>>;
+
+    /// Executes a typed request against `scripts/script.js` and returns its raw JSON output.
+    fn execute_request<'a>(
+        &'a self,
+        request: &'a ScriptRequest,
+        options: &'a ExecutionOptions,
+    ) -> BoxFuture<'a, Result<String, Error>> {
+        Box::pin(async move {
+            let params = request.to_json()?;
+            self.execute(SCRIPT, Some(&params), options).await
+        })
+    }
+}
+
+/// Default `AsyncScriptBackend`, spawning an `osascript` process for each script.
+#[derive(Debug, Clone)]
+pub struct AsyncScriptController {
+    osascript_path: PathBuf,
+}
+
+impl Default for AsyncScriptController {
+    fn default() -> Self {
+        AsyncScriptController {
+            osascript_path: PathBuf::from("osascript"),
+        }
+    }
+}
+
+impl AsyncScriptController {
+    /// Sets the path of the `osascript` executable. Defaults to `osascript`, resolved through `PATH`.
+    pub fn osascript_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.osascript_path = path.as_ref().to_path_buf();
+        self
+    }
+}
+
+impl AsyncScriptBackend for AsyncScriptController {
+    fn execute<'a>(
+        &'a self,
+        script: &'a str,
+        params: Option<&'a str>,
+        options: &'a ExecutionOptions,
+    ) -> BoxFuture<'a, Result<String, Error>> {
+        Box::pin(async move {
+            let start = Instant::now();
+            options.check(start)?;
+
+            let mut command = Command::new(&self.osascript_path);
+            command
+                .arg("-l")
+                .arg("JavaScript")
+                .arg("-e")
+                .arg(script)
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped())
+                .kill_on_drop(true);
+
+            if let Some(params) = params {
+                command.arg(params);
+            }
+
+            let child = command.spawn()?;
+            let output = child.wait_with_output();
+            tokio::pin!(output);
+
+            // Dropping `output` early kills the process, thanks to `kill_on_drop`.
+            let output = loop {
+                tokio::select! {
+                    output = &mut output => break output?,
+                    _ = tokio::time::sleep(POLL_INTERVAL), if options.is_bounded() => options.check(start)?,
+                }
+            };
+
+            if !output.status.success() {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                let err = ScriptError::from_stderr(&stderr, output.status.code(), script, params);
+                return Err(err.into());
+            }
+
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        })
+    }
+}
+
+/// Async counterpart of `AppleMusicClient`, available with the `async` feature.
+///
+/// Every method of `AppleMusic`, as well as the commands of `Track` and `Playlist`, is mirrored here.
+#[derive(Clone)]
+pub struct AsyncAppleMusicClient {
+    backend: Arc<dyn AsyncScriptBackend>,
+    config: ClientConfig,
+}
+
+impl Default for AsyncAppleMusicClient {
+    fn default() -> Self {
+        AsyncAppleMusicClient::builder().build()
+    }
+}
+
+impl AsyncAppleMusicClient {
+    /// Returns a builder to configure a new client.
+    pub fn builder() -> AsyncAppleMusicClientBuilder {
+        AsyncAppleMusicClientBuilder::default()
+    }
+
+    /// Creates a new client running its commands through the provided backend.
+    pub fn with_backend<B>(backend: B) -> Self
+    where
+        B: AsyncScriptBackend + 'static,
+    {
+        AsyncAppleMusicClient::builder().backend(backend).build()
+    }
+
+    /// Returns the backend used by this client.
+    pub fn backend(&self) -> &dyn AsyncScriptBackend {
+        self.backend.as_ref()
+    }
+
+    /// Returns the name of the application targeted by this client.
+    pub fn app_name(&self) -> &str {
+        &self.config.app_name
+    }
+
+    /// Returns a new query on Library Tracks, filtered inside Apple Music.
+    pub fn tracks(&self) -> TrackQuery<&AsyncAppleMusicClient> {
+        TrackQuery::new(self)
+    }
+
+    /// Returns a new query on Playlists, filtered inside Apple Music.
+    pub fn playlists(&self) -> PlaylistQuery<&AsyncAppleMusicClient> {
+        PlaylistQuery::new(self)
+    }
+
+    /// Returns a copy of this client whose scripts are aborted after the provided duration.
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let mut client = self.clone();
+        client.config.options.timeout = Some(timeout);
+        client
+    }
+
+    /// Returns a copy of this client whose scripts are aborted once the provided token is cancelled.
+    pub fn with_cancellation(&self, token: &CancellationToken) -> Self {
+        let mut client = self.clone();
+        client.config.options.cancellation = Some(token.clone());
+        client
+    }
+
+    /// Returns an up-to-date ApplicationData struct.
+    pub async fn get_application_data(&self) -> Result<ApplicationData, Error> {
+        self.call(calls::application_data()).await
+    }
+
+    /// Returns a lightweight handle on the provided Track, running commands without fetching it.
+    pub fn track<T: Into<TrackId>>(&self, track: T) -> TrackRef<AsyncAppleMusicClient> {
+        TrackRef::new(self.clone(), track.into())
+    }
+
+    /// Returns a lightweight handle on the provided Playlist, running commands without fetching it.
+    pub fn playlist<P: Into<PlaylistId>>(&self, playlist: P) -> PlaylistRef<AsyncAppleMusicClient> {
+        PlaylistRef::new(self.clone(), playlist.into())
+    }
+
+    /// Returns the Album with the provided name & album artist, running commands on all of its Tracks.
+    pub fn album(&self, name: &str, artist: &str) -> Album<AsyncAppleMusicClient> {
+        Album::new(self.clone(), name, artist)
+    }
+
+    /// Returns every Album of the library, grouping Library Tracks by album name & album artist.
+    pub async fn get_albums(&self) -> Result<Vec<Album<AsyncAppleMusicClient>>, Error> {
+        let tracks = self.get_all_library_partial_tracks(&ALBUM_FIELDS).await?;
+        Ok(group_albums(self, tracks))
+    }
+
+    /// Returns a new TrackEditor, collecting changes to the provided Track to apply them in a single script.
+    pub fn edit_track<T: Into<TrackId>>(&self, track: T) -> TrackEditor<AsyncAppleMusicClient> {
+        TrackEditor::new(self.clone(), track.into().specifier(&self.app()))
+    }
+
+    /// Returns a new BulkTrackEditor, collecting changes to apply to every provided Track in a single script.
+    pub fn edit_tracks<I, T>(&self, tracks: I) -> BulkTrackEditor<AsyncAppleMusicClient>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<TrackId>,
+    {
+        BulkTrackEditor::for_selection(self.clone(), TrackSelection::tracks(&self.app(), tracks))
+    }
+
+    /// Adds the provided files & folders to the library, or to the provided Playlist, and returns the added Tracks.
+    /// Returns an `Error::InvalidArgument` if any path does not exist or is a file of an unsupported format.
+    pub async fn add<P: AsRef<Path>>(
+        &self,
+        paths: &[P],
+        to_playlist: Option<PlaylistId>,
+    ) -> Result<Vec<Track>, Error> {
+        self.call(calls::add(paths, to_playlist)).await
+    }
+
+    /// Looks for and returns the provided Track, by id or persistent ID, if it exists.
+    pub async fn get_track<T: Into<TrackId>>(&self, track: T) -> Result<Track, Error> {
+        self.call(calls::track(&track.into())).await
+    }
+
+    /// Looks for and returns the provided Playlist, by id or persistent ID, if it exists.
+    pub async fn get_playlist<P: Into<PlaylistId>>(&self, playlist: P) -> Result<Playlist, Error> {
+        self.call(calls::playlist(&playlist.into())).await
+    }
+
+    /// Looks for and returns the Tracks with the provided ids, in a single script.
+    /// Results are in the same order as `ids`, with `None` for every Track which does not exist.
+    pub async fn get_tracks_by_ids(&self, ids: &[i32]) -> Result<Vec<Option<Track>>, Error> {
+        self.call(calls::tracks_by_ids(ids)).await
+    }
+
+    /// Looks for and returns the Tracks with the provided persistent IDs, in a single script.
+    /// Results are in the same order as `persistent_ids`, with `None` for every Track which does not exist.
+    pub async fn get_tracks_by_persistent_ids<S: AsRef<str>>(
+        &self,
+        persistent_ids: &[S],
+    ) -> Result<Vec<Option<Track>>, Error> {
+        self.call(calls::tracks_by_persistent_ids(persistent_ids))
+            .await
+    }
+
+    /// Scans every file Track of the library in a single script, and returns those whose file does not exist anymore.
+    pub async fn find_dead_tracks(&self) -> Result<Vec<DeadTrack>, Error> {
+        self.call(calls::dead_tracks()).await
+    }
+
+    /// Looks for and returns a Playlist based on provided id, if it exists.
+    pub async fn get_playlist_by_id(&self, id: i32) -> Result<Playlist, Error> {
+        self.call(calls::playlist_by_id(id)).await
+    }
+
+    /// Looks for and returns a Playlist based on provided persistent ID, if it exists.
+    /// Unlike `id`, the persistent ID does not change when Apple Music is restarted.
+    pub async fn get_playlist_by_persistent_id(
+        &self,
+        persistent_id: &str,
+    ) -> Result<Playlist, Error> {
+        self.call(calls::playlist_by_persistent_id(persistent_id))
+            .await
+    }
+
+    /// Looks for and returns a Track based on provided persistent ID, if it exists.
+    /// Unlike `id`, the persistent ID does not change when Apple Music is restarted.
+    pub async fn get_track_by_persistent_id(&self, persistent_id: &str) -> Result<Track, Error> {
+        self.call(calls::track_by_persistent_id(persistent_id))
+            .await
+    }
+
+    /// Returns currently playing Track, if any.
+    pub async fn get_current_track(&self) -> Result<Track, Error> {
+        self.call(calls::current_track()).await
+    }
+
+    /// Fetches and returns a list of all Library Tracks, `DEFAULT_PAGE_SIZE` Tracks per script.
+    pub async fn get_all_library_tracks(&self) -> Result<Vec<Track>, Error> {
+        self.library_track_pages(DEFAULT_PAGE_SIZE)
+            .collect_tracks()
+            .await
+    }
+
+    /// Returns a cursor fetching Library Tracks page by page, `page_size` Tracks per script.
+    pub fn library_track_pages(&self, page_size: usize) -> AsyncTrackPages {
+        AsyncTrackPages::new(self.clone(), calls::library_tracks(), page_size)
+    }
+
+    /// Fetches and returns all Library Tracks, only holding the provided fields.
+    pub async fn get_all_library_partial_tracks(
+        &self,
+        fields: &[TrackField],
+    ) -> Result<Vec<PartialTrack>, Error> {
+        self.call(calls::page_tracks(calls::library_tracks().fields(fields)))
+            .await
+    }
+
+    /// Returns a cursor fetching Library Tracks page by page, only holding the provided fields.
+    /// Every field is fetched for the whole collection in a single script, then handed out `page_size` Tracks at a time.
+    pub fn library_partial_track_pages(
+        &self,
+        fields: &[TrackField],
+        page_size: usize,
+    ) -> AsyncTrackPages<PartialTrack> {
+        AsyncTrackPages::new(
+            self.clone(),
+            calls::library_tracks().fields(fields),
+            page_size,
+        )
+    }
+
+    /// Returns the number of Library Tracks, without fetching them.
+    pub async fn count_library_tracks(&self) -> Result<usize, Error> {
+        self.tracks().count().await
+    }
+
+    /// Returns the number of Playlists, without fetching them.
+    pub async fn count_playlists(&self) -> Result<usize, Error> {
+        self.playlists().count().await
+    }
+
+    /// Returns true if the provided Track exists, without fetching it.
+    pub async fn track_exists<T: Into<TrackId>>(&self, track: T) -> Result<bool, Error> {
+        self.call(calls::track_exists(&track.into())).await
+    }
+
+    /// Returns true if the provided Playlist exists, without fetching it.
+    pub async fn playlist_exists<P: Into<PlaylistId>>(&self, playlist: P) -> Result<bool, Error> {
+        self.call(calls::playlist_exists(&playlist.into())).await
+    }
+
+    /// Plays the provided Track on AppleMusic player.
+    pub async fn play_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        self.execute(&commands::play_track(&self.app(), &track.into()))
+            .await
+    }
+
+    /// Plays the provided Playlist on AppleMusic player.
+    pub async fn play_playlist<P: Into<PlaylistId>>(&self, playlist: P) -> Result<(), Error> {
+        self.execute(&commands::play_playlist(&self.app(), &playlist.into()))
+            .await
+    }
+
+    /// Mutes / Unmutes AppleMusic player.
+    pub async fn set_mute(&self, value: bool) -> Result<(), Error> {
+        self.execute(&commands::set_mute(&self.app(), value)).await
+    }
+
+    /// Activates / Deactivates Shuffle mode on AppleMusic player.
+    pub async fn set_shuffle(&self, value: bool) -> Result<(), Error> {
+        self.execute(&commands::set_shuffle(&self.app(), value))
+            .await
+    }
+
+    /// Sets Song Repeat mode to provided value.
+    pub async fn set_song_repeat_mode(&self, value: SongRepeatMode) -> Result<(), Error> {
+        self.execute(&commands::set_song_repeat_mode(&self.app(), &value))
+            .await
+    }
+
+    /// Sets Sound Volume to provided value. ( 0 <= value <= 100 )
+    pub async fn set_sound_volume(&self, value: i8) -> Result<(), Error> {
+        self.execute(&commands::set_sound_volume(&self.app(), value))
+            .await
+    }
+
+    /// Tries to convert the provided Track.
+    pub async fn convert_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        self.execute(&commands::convert_track(&self.app(), &track.into()))
+            .await
+    }
+
+    /// Resumes the player if a track is Paused, otherwise Plays a Track from Library.
+    pub async fn play(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "play")).await
+    }
+
+    /// Pauses the player's currently playing Track.
+    pub async fn pause(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "pause")).await
+    }
+
+    /// Stops Rewinding / Fast-Forwarding and plays the Track at normal speed.
+    pub async fn resume(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "resume")).await
+    }
+
+    /// Restart the current Track.
+    pub async fn back_track(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "backTrack"))
+            .await
+    }
+
+    /// Fast-forwards the current Track up until resuming or end of current Track.
+    pub async fn fast_forward(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "fastForward"))
+            .await
+    }
+
+    /// Skips current Track and plays next one.
+    pub async fn next_track(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "nextTrack"))
+            .await
+    }
+
+    /// Plays if Player is currently Paused, Pauses if Player is currently Playing.
+    pub async fn playpause(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "playpause"))
+            .await
+    }
+
+    /// Goes back to previous Track and plays it.
+    pub async fn previous_track(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "previousTrack"))
+            .await
+    }
+
+    /// Rewinds current Track up until resuming or start of Track.
+    pub async fn rewind(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "rewind")).await
+    }
+
+    /// Stops player, removing enqueued Tracks and currently playing Track.
+    pub async fn stop(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "stop")).await
+    }
+
+    /// Fully Quits Apple Music.
+    pub async fn quit(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "quit")).await
+    }
+
+    /// Opens Apple Music app.
+    pub async fn run(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "run")).await
+    }
+
+    /// Returns a list of all artworks of the provided Track, with their raw_data.
+    pub async fn fetch_track_artworks<T: Into<TrackId>>(
+        &self,
+        track: T,
+    ) -> Result<Vec<Artwork>, Error> {
+        self.call(calls::artworks(&track.into())).await
+    }
+
+    /// Adds the provided image as the last artwork of the provided Track.
+    /// Returns an `Error::InvalidArgument` if the image is not a PNG, JPEG or TIFF image.
+    pub async fn add_track_artwork<T: Into<TrackId>>(
+        &self,
+        track: T,
+        image: &ArtworkImage,
+    ) -> Result<(), Error> {
+        let tracks = commands::tracks(&self.app(), &track.into());
+        self.call(calls::add_artwork(&self.app(), &tracks, image))
+            .await
+    }
+
+    /// Replaces the first artwork of the provided Track with the provided image, or adds it if there is none.
+    /// Returns an `Error::InvalidArgument` if the image is not a PNG, JPEG or TIFF image.
+    pub async fn replace_track_artwork<T: Into<TrackId>>(
+        &self,
+        track: T,
+        image: &ArtworkImage,
+    ) -> Result<(), Error> {
+        let tracks = commands::tracks(&self.app(), &track.into());
+        self.call(calls::replace_artwork(&self.app(), &tracks, image))
+            .await
+    }
+
+    /// Deletes every artwork of the provided Track.
+    pub async fn remove_track_artworks<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        let tracks = commands::tracks(&self.app(), &track.into());
+        self.execute(&commands::remove_artworks(&tracks)).await
+    }
+
+    /// Updates the provided file Track from the metadata of its file, e.g. after its tags were rewritten.
+    /// Returns an `Error::NotFileTrack` if the Track is not a file Track.
+    pub async fn refresh_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        let results = self
+            .refresh(TrackSelection::tracks(&self.app(), [track]))
+            .await?;
+
+        results
+            .into_iter()
+            .next()
+            .map_or(Ok(()), |refresh| refresh.result)
+    }
+
+    /// Updates every provided file Track from the metadata of its file, in a single script, and returns their results.
+    /// Tracks which are not file Tracks fail with an `Error::NotFileTrack`, without preventing the others from being refreshed.
+    pub async fn refresh_tracks<I, T>(&self, tracks: I) -> Result<Vec<TrackResult>, Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<TrackId>,
+    {
+        self.refresh(TrackSelection::tracks(&self.app(), tracks))
+            .await
+    }
+
+    /// Reveals and selects provided Track in Apple Music.
+    pub async fn reveal_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        self.execute(&commands::reveal_track(&self.app(), &track.into()))
+            .await
+    }
+
+    /// Triggers a download on Apple Music Player for the provided Track.
+    pub async fn download_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        self.execute(&commands::download_track(&self.app(), &track.into()))
+            .await
+    }
+
+    /// Favorites / "Unfavorites" provided Track.
+    pub async fn set_track_favorited<T: Into<TrackId>>(
+        &self,
+        track: T,
+        value: bool,
+    ) -> Result<(), Error> {
+        self.execute(&commands::set_track_favorited(
+            &self.app(),
+            &track.into(),
+            value,
+        ))
+        .await
+    }
+
+    /// Dislikes / "Undislikes" provided Track.
+    pub async fn set_track_disliked<T: Into<TrackId>>(
+        &self,
+        track: T,
+        value: bool,
+    ) -> Result<(), Error> {
+        self.execute(&commands::set_track_disliked(
+            &self.app(),
+            &track.into(),
+            value,
+        ))
+        .await
+    }
+
+    /// Search for provided Track in the Itunes Store and extract its artwork_url & track_url.
+    pub async fn fetch_itunes_store_data(&self, track: &mut Track) -> Result<(), Error> {
+        for request in track.itunes_store_requests() {
+            let body = reqwest::get(request)
+                .await?
+                .error_for_status()?
+                .text()
+                .await?;
+            track.apply_itunes_store_search(&body);
+
+            if track.artwork_url.is_some() {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fetches and returns the Tracks of provided Playlist, `DEFAULT_PAGE_SIZE` Tracks per script.
+    pub async fn fetch_playlist_tracks<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+    ) -> Result<Vec<Track>, Error> {
+        self.playlist_track_pages(playlist, DEFAULT_PAGE_SIZE)
+            .collect_tracks()
+            .await
+    }
+
+    /// Returns a cursor fetching the Tracks of provided Playlist page by page, `page_size` Tracks per script.
+    pub fn playlist_track_pages<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+        page_size: usize,
+    ) -> AsyncTrackPages {
+        AsyncTrackPages::new(
+            self.clone(),
+            calls::playlist_tracks(&playlist.into()),
+            page_size,
+        )
+    }
+
+    /// Fetches and returns the Tracks of provided Playlist, only holding the provided fields.
+    pub async fn fetch_playlist_partial_tracks<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+        fields: &[TrackField],
+    ) -> Result<Vec<PartialTrack>, Error> {
+        let request = calls::playlist_tracks(&playlist.into()).fields(fields);
+        self.call(calls::page_tracks(request)).await
+    }
+
+    /// Returns a cursor fetching the Tracks of provided Playlist page by page, only holding the provided fields.
+    /// Every field is fetched for the whole collection in a single script, then handed out `page_size` Tracks at a time.
+    pub fn playlist_partial_track_pages<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+        fields: &[TrackField],
+        page_size: usize,
+    ) -> AsyncTrackPages<PartialTrack> {
+        AsyncTrackPages::new(
+            self.clone(),
+            calls::playlist_tracks(&playlist.into()).fields(fields),
+            page_size,
+        )
+    }
+
+    /// Searches the whole library for Tracks matching provided query, as Apple Music's search field does.
+    pub async fn search(&self, query: &str, kind: SearchKind) -> Result<Vec<Track>, Error> {
+        self.search_in(SearchTarget::Library, query, kind).await
+    }
+
+    /// Searches inside provided Playlist or source for Tracks matching provided query.
+    pub async fn search_in<S: Into<SearchTarget>>(
+        &self,
+        target: S,
+        query: &str,
+        kind: SearchKind,
+    ) -> Result<Vec<Track>, Error> {
+        self.call(calls::search(target.into(), query, kind)).await
+    }
+
+    /// Searches inside provided Playlist for Tracks containing provided query and returns them.
+    pub async fn search_in_playlist<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+        query: &str,
+    ) -> Result<Vec<Track>, Error> {
+        self.call(calls::search_in_playlist(&playlist.into(), query))
+            .await
+    }
+
+    /// Reveals and selects provided Playlist in Apple Music player.
+    pub async fn reveal_playlist<P: Into<PlaylistId>>(&self, playlist: P) -> Result<(), Error> {
+        self.execute(&commands::reveal_playlist(&self.app(), &playlist.into()))
+            .await
+    }
+
+    /// Triggers a download on Apple Music Player for the provided Playlist.
+    pub async fn download_playlist<P: Into<PlaylistId>>(&self, playlist: P) -> Result<(), Error> {
+        self.execute(&commands::download_playlist(&self.app(), &playlist.into()))
+            .await
+    }
+
+    /// Returns the specifier of the targeted application, e.g. `Application("Music")`.
+    pub(crate) fn app(&self) -> Jxa {
+        self.config.app()
+    }
+
+    /// Runs the call, awaiting the end of its script.
+    pub(crate) async fn call<T>(&self, call: Call<T>) -> Result<T, Error> {
+        let result = match call {
+            Call::Done(result) => result,
+            Call::Request(request, output) => {
+                let request = self.config.prepare(request);
+                output(
+                    self.backend
+                        .execute_request(&request, &self.config.options)
+                        .await,
+                )
+            }
+            Call::Script(script, output) => {
+                self.config.log_script(&script);
+                output(
+                    self.backend
+                        .execute(&script, None, &self.config.options)
+                        .await,
+                )
+            }
+        };
+
+        self.config.report(result)
+    }
+
+    pub(crate) async fn execute(&self, cmd: &str) -> Result<(), Error> {
+        self.call(Call::script(cmd)).await
+    }
+
+    pub(crate) async fn refresh(&self, tracks: TrackSelection) -> Result<Vec<TrackResult>, Error> {
+        self.call(calls::refresh(&self.app(), tracks)).await
+    }
+
+    /// Executes a raw script and returns its output.
+    pub(crate) async fn execute_script(&self, cmd: &str) -> Result<String, Error> {
+        self.call(Call::output(cmd)).await
+    }
+
+    pub(crate) async fn execute_request<T>(&self, request: &ScriptRequest) -> Result<T, Error>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        self.call(Call::request(request.clone())).await
+    }
+}
+
+/// Async counterpart of `TrackPages`, fetching the pages of a Track collection one script at a time.
+///
+/// ```rust,ignore
+/// let mut pages = client.library_track_pages(1000);
+///
+/// while let Some(page) = pages.next_page().await {
+///     let page = page?;
+///     println!("{} / {}", page.end, page.total);
+/// }
+/// ```
+pub struct AsyncTrackPages<T = Track> {
+    client: AsyncAppleMusicClient,
+    request: ScriptRequest,
+    cursor: PageCursor<T>,
+}
+
+impl<T: DeserializeOwned + 'static> AsyncTrackPages<T> {
+    pub(crate) fn new(
+        client: AsyncAppleMusicClient,
+        request: ScriptRequest,
+        page_size: usize,
+    ) -> Self {
+        AsyncTrackPages {
+            client,
+            cursor: PageCursor::new(&request, page_size),
+            request,
+        }
+    }
+
+    /// Fetches and returns the next page, or `None` once the whole collection has been fetched.
+    pub async fn next_page(&mut self) -> Option<Result<TrackPage<T>, Error>> {
+        if let Some(page) = self.cursor.fetched_page() {
+            return Some(Ok(page));
+        }
+
+        let request = self.cursor.next_request(&self.request)?;
+        let result = self.client.execute_request(&request).await;
+
+        Some(self.cursor.advance(result))
+    }
+
+    /// Fetches every remaining page and returns all of their Tracks.
+    pub async fn collect_tracks(mut self) -> Result<Vec<T>, Error> {
+        let mut tracks = Vec::new();
+
+        while let Some(page) = self.next_page().await {
+            tracks.extend(page?.tracks);
+        }
+
+        Ok(tracks)
+    }
+}
+
+/// Builder of an `AsyncAppleMusicClient`.
+pub struct AsyncAppleMusicClientBuilder {
+    app_name: String,
+    osascript_path: PathBuf,
+    timeout: Option<Duration>,
+    logging: bool,
+    backend: Option<Arc<dyn AsyncScriptBackend>>,
+}
+
+impl Default for AsyncAppleMusicClientBuilder {
+    fn default() -> Self {
+        AsyncAppleMusicClientBuilder {
+            app_name: String::from("Music"),
+            osascript_path: PathBuf::from("osascript"),
+            timeout: None,
+            logging: true,
+            backend: None,
+        }
+    }
+}
+
+impl AsyncAppleMusicClientBuilder {
+    /// Sets the name of the targeted application. Defaults to `Music`.
+    pub fn app_name(mut self, app_name: &str) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// Sets the path of the `osascript` executable used by the default backend.
+    pub fn osascript_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.osascript_path = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Sets the maximum duration of every script. No timeout by default.
+    /// It can be overridden per call through `AsyncAppleMusicClient::with_timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Enables / Disables logging of executed scripts and failures. Enabled by default.
+    pub fn logging(mut self, value: bool) -> Self {
+        self.logging = value;
+        self
+    }
+
+    /// Sets a custom backend. `osascript_path` is then ignored.
+    pub fn backend<B>(mut self, backend: B) -> Self
+    where
+        B: AsyncScriptBackend + 'static,
+    {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Builds the configured `AsyncAppleMusicClient`.
+    pub fn build(self) -> AsyncAppleMusicClient {
+        let backend = match self.backend {
+            Some(backend) => backend,
+            None => Arc::new(AsyncScriptController::default().osascript_path(self.osascript_path)),
+        };
+
+        AsyncAppleMusicClient {
+            backend,
+            config: ClientConfig::new(self.app_name, self.logging, self.timeout),
+        }
+    }
+}
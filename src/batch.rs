@@ -0,0 +1,347 @@
+use crate::apple_music::SongRepeatMode;
+use crate::calls::parse_output;
+use crate::client::AppleMusicClient;
+use crate::commands;
+use crate::error::{Error, ScriptError};
+use crate::id::{PlaylistId, TrackId};
+use crate::jxa::Jxa;
+use crate::track::TrackKind;
+use serde::{de, Deserialize};
+
+/// Collection of commands compiled into a single script, and executed in one round-trip.
+///
+/// Every command is run even if a previous one failed, and gets its own result:
+/// ```rust,ignore
+/// let results = client
+///     .batch()
+///     .set_shuffle(true)
+///     .set_song_repeat_mode(SongRepeatMode::ALL)
+///     .set_sound_volume(40)
+///     .play_playlist(&playlist)
+///     .execute()?;
+/// ```
+pub struct Batch<'a> {
+    client: &'a AppleMusicClient,
+    app: Jxa,
+    commands: Vec<String>,
+}
+
+/// Failure of a single command of a script, caught inside the script itself.
+#[derive(Deserialize)]
+pub(crate) struct BatchFailure {
+    message: String,
+    code: Option<i32>,
+    kind: Option<TrackKind>,
+}
+
+impl BatchFailure {
+    /// Returns the Error of the failed command.
+    pub(crate) fn into_error(self, script: &str) -> Error {
+        if let Some(kind) = self.kind {
+            return Error::NotFileTrack(kind);
+        }
+
+        ScriptError {
+            code: self.code,
+            message: self.message,
+            exit_code: None,
+            script: script.into(),
+            params: None,
+        }
+        .into()
+    }
+}
+
+impl<'a> Batch<'a> {
+    pub(crate) fn new(client: &'a AppleMusicClient, app: Jxa) -> Self {
+        Batch {
+            client,
+            app,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Returns the number of commands in the Batch.
+    pub fn len(&self) -> usize {
+        self.commands.len()
+    }
+
+    /// Returns true if the Batch does not contain any command.
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+
+    /// Plays the provided Track on AppleMusic player.
+    pub fn play_track<T: Into<TrackId>>(self, track: T) -> Self {
+        let cmd = commands::play_track(&self.app, &track.into());
+        self.push(cmd)
+    }
+
+    /// Plays the provided Playlist on AppleMusic player.
+    pub fn play_playlist<P: Into<PlaylistId>>(self, playlist: P) -> Self {
+        let cmd = commands::play_playlist(&self.app, &playlist.into());
+        self.push(cmd)
+    }
+
+    /// Mutes / Unmutes AppleMusic player.
+    pub fn set_mute(self, value: bool) -> Self {
+        let cmd = commands::set_mute(&self.app, value);
+        self.push(cmd)
+    }
+
+    /// Activates / Deactivates Shuffle mode on AppleMusic player.
+    pub fn set_shuffle(self, value: bool) -> Self {
+        let cmd = commands::set_shuffle(&self.app, value);
+        self.push(cmd)
+    }
+
+    /// Sets Song Repeat mode to provided value.
+    pub fn set_song_repeat_mode(self, value: SongRepeatMode) -> Self {
+        let cmd = commands::set_song_repeat_mode(&self.app, &value);
+        self.push(cmd)
+    }
+
+    /// Sets Sound Volume to provided value. ( 0 <= value <= 100 )
+    pub fn set_sound_volume(self, value: i8) -> Self {
+        let cmd = commands::set_sound_volume(&self.app, value);
+        self.push(cmd)
+    }
+
+    /// Tries to convert the provided Track.
+    pub fn convert_track<T: Into<TrackId>>(self, track: T) -> Self {
+        let cmd = commands::convert_track(&self.app, &track.into());
+        self.push(cmd)
+    }
+
+    /// Resumes the player if a track is Paused, otherwise Plays a Track from Library.
+    pub fn play(self) -> Self {
+        self.call("play")
+    }
+
+    /// Pauses the player's currently playing Track.
+    pub fn pause(self) -> Self {
+        self.call("pause")
+    }
+
+    /// Stops Rewinding / Fast-Forwarding and plays the Track at normal speed.
+    pub fn resume(self) -> Self {
+        self.call("resume")
+    }
+
+    /// Restart the current Track.
+    pub fn back_track(self) -> Self {
+        self.call("backTrack")
+    }
+
+    /// Fast-forwards the current Track up until resuming or end of current Track.
+    pub fn fast_forward(self) -> Self {
+        self.call("fastForward")
+    }
+
+    /// Skips current Track and plays next one.
+    pub fn next_track(self) -> Self {
+        self.call("nextTrack")
+    }
+
+    /// Plays if Player is currently Paused, Pauses if Player is currently Playing.
+    pub fn playpause(self) -> Self {
+        self.call("playpause")
+    }
+
+    /// Goes back to previous Track and plays it.
+    pub fn previous_track(self) -> Self {
+        self.call("previousTrack")
+    }
+
+    /// Rewinds current Track up until resuming or start of Track.
+    pub fn rewind(self) -> Self {
+        self.call("rewind")
+    }
+
+    /// Stops player, removing enqueued Tracks and currently playing Track.
+    pub fn stop(self) -> Self {
+        self.call("stop")
+    }
+
+    /// Reveals and selects provided Track in Apple Music.
+    pub fn reveal_track<T: Into<TrackId>>(self, track: T) -> Self {
+        let cmd = commands::reveal_track(&self.app, &track.into());
+        self.push(cmd)
+    }
+
+    /// Triggers a download on Apple Music Player for the provided Track.
+    pub fn download_track<T: Into<TrackId>>(self, track: T) -> Self {
+        let cmd = commands::download_track(&self.app, &track.into());
+        self.push(cmd)
+    }
+
+    /// Favorites / "Unfavorites" provided Track.
+    pub fn set_track_favorited<T: Into<TrackId>>(self, track: T, value: bool) -> Self {
+        let cmd = commands::set_track_favorited(&self.app, &track.into(), value);
+        self.push(cmd)
+    }
+
+    /// Dislikes / "Undislikes" provided Track.
+    pub fn set_track_disliked<T: Into<TrackId>>(self, track: T, value: bool) -> Self {
+        let cmd = commands::set_track_disliked(&self.app, &track.into(), value);
+        self.push(cmd)
+    }
+
+    /// Reveals and selects provided Playlist in Apple Music player.
+    pub fn reveal_playlist<P: Into<PlaylistId>>(self, playlist: P) -> Self {
+        let cmd = commands::reveal_playlist(&self.app, &playlist.into());
+        self.push(cmd)
+    }
+
+    /// Triggers a download on Apple Music Player for the provided Playlist.
+    pub fn download_playlist<P: Into<PlaylistId>>(self, playlist: P) -> Self {
+        let cmd = commands::download_playlist(&self.app, &playlist.into());
+        self.push(cmd)
+    }
+
+    /// Returns the single script running every command of the Batch.
+    pub fn to_script(&self) -> String {
+        let mut script = String::from("(() => {\n    const results = [];\n");
+
+        for cmd in &self.commands {
+            script.push_str(&format!(
+                "    try {{ {}; results.push(null); }} catch (e) {{ results.push({{message: String(e.message || e), code: e.errorNumber === undefined ? null : e.errorNumber}}); }}\n",
+                cmd
+            ));
+        }
+
+        script.push_str("    return JSON.stringify(results);\n})()");
+        script
+    }
+
+    /// Executes every command of the Batch in a single script, and returns their results in order.
+    /// Fails as a whole only if the script itself could not be run.
+    pub fn execute(self) -> Result<Vec<Result<(), Error>>, Error> {
+        if self.commands.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let output = self.client.execute_script(&self.to_script())?;
+        let failures: Vec<Option<BatchFailure>> = parse_output(&output)?;
+
+        if failures.len() != self.commands.len() {
+            return Err(Error::Deserialization {
+                path: String::new(),
+                source: de::Error::invalid_length(failures.len(), &"one result per command"),
+            });
+        }
+
+        Ok(self
+            .commands
+            .into_iter()
+            .zip(failures)
+            .map(|(cmd, failure)| match failure {
+                None => Ok(()),
+                Some(failure) => Err(failure.into_error(&cmd)),
+            })
+            .collect())
+    }
+
+    fn call(self, method: &str) -> Self {
+        let cmd = commands::call(&self.app, method);
+        self.push(cmd)
+    }
+
+    fn push(mut self, cmd: String) -> Self {
+        self.commands.push(cmd);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{RecordingBackend, Sent};
+
+    fn client() -> (AppleMusicClient, RecordingBackend) {
+        let backend = RecordingBackend::default();
+        (AppleMusicClient::with_backend(backend.clone()), backend)
+    }
+
+    #[test]
+    fn runs_every_command_in_its_own_try() {
+        let (client, _) = client();
+        let batch = client.batch().set_mute(true).play_track(42);
+
+        assert_eq!(batch.len(), 2);
+        assert_eq!(
+            batch.to_script(),
+            r#"(() => {
+    const results = [];
+    try { Application("Music").mute = true; results.push(null); } catch (e) { results.push({message: String(e.message || e), code: e.errorNumber === undefined ? null : e.errorNumber}); }
+    try { Application("Music").play(Application("Music").tracks.byId(42)); results.push(null); } catch (e) { results.push({message: String(e.message || e), code: e.errorNumber === undefined ? null : e.errorNumber}); }
+    return JSON.stringify(results);
+})()"#
+        );
+    }
+
+    #[test]
+    fn returns_the_result_of_every_command() {
+        let (client, backend) = client();
+        backend.respond(
+            r#"[null, {"message": "Can't get object.", "code": -1728}, {"message": "Oops", "code": null}, {"message": "Not a file", "code": null, "kind": "urlTrack"}]"#,
+        );
+
+        let batch = client
+            .batch()
+            .play()
+            .play_track(42)
+            .pause()
+            .convert_track(7);
+        let script = batch.to_script();
+        let results = batch.execute().unwrap();
+
+        assert_eq!(backend.sent(), [Sent::Script(script)]);
+        assert!(results[0].is_ok());
+        match &results[1] {
+            Err(Error::ObjectNotFound(err)) => {
+                assert_eq!(err.message, "Can't get object.");
+                assert_eq!(
+                    err.script,
+                    r#"Application("Music").play(Application("Music").tracks.byId(42))"#
+                );
+            }
+            result => panic!("expected ObjectNotFound, got {:?}", result),
+        }
+        assert!(matches!(&results[2], Err(Error::ScriptFailed(err)) if err.message == "Oops"));
+        assert!(matches!(
+            &results[3],
+            Err(Error::NotFileTrack(TrackKind::UrlTrack))
+        ));
+    }
+
+    #[test]
+    fn rejects_missing_results() {
+        let (client, backend) = client();
+        backend.respond("[null]");
+
+        assert!(matches!(
+            client.batch().play().pause().execute(),
+            Err(Error::Deserialization { .. })
+        ));
+    }
+
+    #[test]
+    fn fails_as_a_whole_if_the_script_fails() {
+        let (client, backend) = client();
+        backend.fail(Error::Cancelled);
+
+        assert!(matches!(
+            client.batch().play().execute(),
+            Err(Error::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn skips_empty_batches() {
+        let (client, backend) = client();
+
+        assert!(client.batch().execute().unwrap().is_empty());
+        assert!(backend.sent().is_empty());
+    }
+}
@@ -0,0 +1,283 @@
+//! Calls shared by every client: each one holds the request or script to run, and turns its output into a result.
+//! `AppleMusicClient` & `AsyncAppleMusicClient` only differ in how they run them, blocking or awaiting.
+use crate::application_data::ApplicationData;
+use crate::artwork::{ArtworkImage, TempImage};
+use crate::commands;
+use crate::error::Error;
+use crate::id::{PlaylistId, TrackId};
+use crate::import::validate_paths;
+use crate::jxa::Jxa;
+use crate::paging::TrackPage;
+use crate::playlist::Playlist;
+use crate::script_controller::{ExecutionOptions, ParamType, ScriptRequest};
+use crate::search::{SearchKind, SearchTarget};
+use crate::selection::{TrackResult, TrackSelection};
+use crate::track::{Artwork, DeadTrack, Track};
+use log::{debug, error};
+use serde::de::DeserializeOwned;
+use std::path::Path;
+use std::time::Duration;
+
+/// Turns the output of the script of a `Call` into its result.
+pub(crate) type Output<T> = Box<dyn FnOnce(Result<String, Error>) -> Result<T, Error> + Send>;
+
+/// Operation run by a client, from the request or script to run to its result.
+pub(crate) enum Call<T> {
+    /// Result known without running any script, e.g. when fetching an empty list of Tracks
+    Done(Result<T, Error>),
+
+    /// Typed request against `scripts/script.js`
+    Request(ScriptRequest, Output<T>),
+
+    /// Raw JXA script
+    Script(String, Output<T>),
+}
+
+impl<T: DeserializeOwned + 'static> Call<T> {
+    /// Runs the request and deserializes its JSON output.
+    pub(crate) fn request(request: ScriptRequest) -> Self {
+        Call::Request(request, Box::new(|output| parse_output(&output?)))
+    }
+}
+
+impl Call<()> {
+    /// Runs the script and ignores its output.
+    pub(crate) fn script(script: &str) -> Self {
+        Call::Script(script.into(), Box::new(|output| output.map(|_| ())))
+    }
+}
+
+impl Call<String> {
+    /// Runs the script and returns its raw output.
+    pub(crate) fn output(script: &str) -> Self {
+        Call::Script(script.into(), Box::new(|output| output))
+    }
+}
+
+/// Settings shared by every client: the targeted application, logging & execution options.
+#[derive(Debug, Clone)]
+pub(crate) struct ClientConfig {
+    pub(crate) app_name: String,
+    pub(crate) logging: bool,
+    pub(crate) options: ExecutionOptions,
+}
+
+impl ClientConfig {
+    pub(crate) fn new(app_name: String, logging: bool, timeout: Option<Duration>) -> Self {
+        ClientConfig {
+            app_name,
+            logging,
+            options: ExecutionOptions {
+                timeout,
+                cancellation: None,
+            },
+        }
+    }
+
+    /// Returns the specifier of the targeted application, e.g. `Application("Music")`.
+    pub(crate) fn app(&self) -> Jxa {
+        Jxa::application(&self.app_name)
+    }
+
+    /// Returns the request, targeting the configured application.
+    pub(crate) fn prepare(&self, request: ScriptRequest) -> ScriptRequest {
+        let request = request.app(&self.app_name);
+
+        if self.logging {
+            debug!("Executing request: {:?}", request);
+        }
+
+        request
+    }
+
+    pub(crate) fn log_script(&self, script: &str) {
+        if self.logging {
+            debug!("Executing script: {}", script);
+        }
+    }
+
+    /// Logs the failure of a call, if any.
+    pub(crate) fn report<T>(&self, result: Result<T, Error>) -> Result<T, Error> {
+        result.inspect_err(|err| {
+            if self.logging {
+                error!("{:#?}", err);
+            }
+        })
+    }
+}
+
+/// Deserializes the JSON output of a script, reporting the path of the failing field, if any.
+pub(crate) fn parse_output<T: DeserializeOwned>(output: &str) -> Result<T, Error> {
+    let deserializer = &mut serde_json::Deserializer::from_str(output);
+    Ok(serde_path_to_error::deserialize(deserializer)?)
+}
+
+pub(crate) fn application_data() -> Call<ApplicationData> {
+    Call::request(ScriptRequest::new(ParamType::ApplicationData))
+}
+
+pub(crate) fn add<P: AsRef<Path>>(
+    paths: &[P],
+    to_playlist: Option<PlaylistId>,
+) -> Call<Vec<Track>> {
+    let paths = match validate_paths(paths) {
+        Ok(paths) if paths.is_empty() => return Call::Done(Ok(Vec::new())),
+        Ok(paths) => paths,
+        Err(err) => return Call::Done(Err(err)),
+    };
+
+    let mut request = ScriptRequest::new(ParamType::Add).paths(&paths);
+
+    if let Some(playlist) = &to_playlist {
+        request = request.playlist(playlist);
+    }
+
+    Call::request(request)
+}
+
+pub(crate) fn track(track: &TrackId) -> Call<Track> {
+    Call::request(ScriptRequest::new(ParamType::TrackById).track(track))
+}
+
+pub(crate) fn playlist(playlist: &PlaylistId) -> Call<Playlist> {
+    Call::request(ScriptRequest::new(ParamType::PlaylistById).playlist(playlist))
+}
+
+pub(crate) fn tracks_by_ids(ids: &[i32]) -> Call<Vec<Option<Track>>> {
+    if ids.is_empty() {
+        return Call::Done(Ok(Vec::new()));
+    }
+
+    Call::request(ScriptRequest::new(ParamType::TracksByIds).ids(ids))
+}
+
+pub(crate) fn tracks_by_persistent_ids<S: AsRef<str>>(
+    persistent_ids: &[S],
+) -> Call<Vec<Option<Track>>> {
+    if persistent_ids.is_empty() {
+        return Call::Done(Ok(Vec::new()));
+    }
+
+    Call::request(ScriptRequest::new(ParamType::TracksByIds).persistent_ids(persistent_ids))
+}
+
+pub(crate) fn dead_tracks() -> Call<Vec<DeadTrack>> {
+    Call::Request(
+        ScriptRequest::new(ParamType::FileTrackLocations),
+        Box::new(|output| {
+            let tracks: Vec<DeadTrack> = parse_output(&output?)?;
+            Ok(tracks.into_iter().filter(DeadTrack::is_dead).collect())
+        }),
+    )
+}
+
+pub(crate) fn playlist_by_id(id: i32) -> Call<Playlist> {
+    Call::request(ScriptRequest::new(ParamType::PlaylistById).id(id))
+}
+
+pub(crate) fn playlist_by_persistent_id(persistent_id: &str) -> Call<Playlist> {
+    Call::request(
+        ScriptRequest::new(ParamType::PlaylistByPersistentId).persistent_id(persistent_id),
+    )
+}
+
+pub(crate) fn track_by_persistent_id(persistent_id: &str) -> Call<Track> {
+    Call::request(ScriptRequest::new(ParamType::TrackByPersistentId).persistent_id(persistent_id))
+}
+
+pub(crate) fn current_track() -> Call<Track> {
+    Call::Request(
+        ScriptRequest::new(ParamType::CurrentTrack),
+        Box::new(|output| match output {
+            Err(Error::ObjectNotFound(_)) => Err(Error::NotPlaying),
+            output => parse_output(&output?),
+        }),
+    )
+}
+
+/// Returns the request fetching Library Tracks, to be paged.
+pub(crate) fn library_tracks() -> ScriptRequest {
+    ScriptRequest::new(ParamType::AllTracks)
+}
+
+/// Returns the request fetching the Tracks of the provided Playlist, to be paged.
+pub(crate) fn playlist_tracks(playlist: &PlaylistId) -> ScriptRequest {
+    ScriptRequest::new(ParamType::PlaylistTracks).playlist(playlist)
+}
+
+/// Fetches every Track of the request in a single page, e.g. `library_tracks().fields(fields)`.
+pub(crate) fn page_tracks<T: DeserializeOwned + 'static>(request: ScriptRequest) -> Call<Vec<T>> {
+    Call::Request(
+        request,
+        Box::new(|output| {
+            let page: TrackPage<T> = parse_output(&output?)?;
+            Ok(page.tracks)
+        }),
+    )
+}
+
+pub(crate) fn track_exists(track: &TrackId) -> Call<bool> {
+    Call::request(ScriptRequest::new(ParamType::TrackExists).track(track))
+}
+
+pub(crate) fn playlist_exists(playlist: &PlaylistId) -> Call<bool> {
+    Call::request(ScriptRequest::new(ParamType::PlaylistExists).playlist(playlist))
+}
+
+pub(crate) fn artworks(track: &TrackId) -> Call<Vec<Artwork>> {
+    Call::request(ScriptRequest::new(ParamType::Artworks).track(track))
+}
+
+pub(crate) fn add_artwork(app: &Jxa, tracks: &Jxa, image: &ArtworkImage) -> Call<()> {
+    with_image(image, |path| commands::add_artwork(app, tracks, path))
+}
+
+pub(crate) fn replace_artwork(app: &Jxa, tracks: &Jxa, image: &ArtworkImage) -> Call<()> {
+    with_image(image, |path| commands::replace_artwork(app, tracks, path))
+}
+
+pub(crate) fn refresh(app: &Jxa, tracks: TrackSelection) -> Call<Vec<TrackResult>> {
+    if tracks.is_empty() {
+        return Call::Done(Ok(Vec::new()));
+    }
+
+    let script = tracks.script(&commands::refresh(app));
+    Call::Script(
+        script.clone(),
+        Box::new(move |output| tracks.results(&script, &output?)),
+    )
+}
+
+pub(crate) fn search(target: SearchTarget, query: &str, kind: SearchKind) -> Call<Vec<Track>> {
+    let request = ScriptRequest::new(ParamType::Search)
+        .query(query)
+        .kind(kind);
+    Call::request(target.target(request))
+}
+
+pub(crate) fn search_in_playlist(playlist: &PlaylistId, query: &str) -> Call<Vec<Track>> {
+    Call::request(
+        ScriptRequest::new(ParamType::SearchInPlaylist)
+            .playlist(playlist)
+            .query(query),
+    )
+}
+
+/// Runs the script reading the image from a temporary file, kept until the script is over.
+fn with_image<F>(image: &ArtworkImage, script: F) -> Call<()>
+where
+    F: FnOnce(&Path) -> String,
+{
+    let file = match TempImage::new(image) {
+        Ok(file) => file,
+        Err(err) => return Call::Done(Err(err)),
+    };
+
+    Call::Script(
+        script(file.path()),
+        Box::new(move |output| {
+            drop(file);
+            output.map(|_| ())
+        }),
+    )
+}
@@ -0,0 +1,756 @@
+use crate::album::{group_albums, Album, ALBUM_FIELDS};
+use crate::apple_music::SongRepeatMode;
+use crate::application_data::ApplicationData;
+use crate::artwork::ArtworkImage;
+use crate::batch::Batch;
+use crate::calls::{self, Call, ClientConfig};
+use crate::commands;
+use crate::editor::{BulkTrackEditor, TrackEditor};
+use crate::error::Error;
+use crate::handle::{PlaylistRef, TrackRef};
+use crate::id::{PlaylistId, TrackId};
+use crate::jxa::Jxa;
+use crate::paging::{TrackPages, DEFAULT_PAGE_SIZE};
+use crate::partial_track::{PartialTrack, TrackField};
+use crate::playlist::Playlist;
+use crate::query::{PlaylistQuery, TrackQuery};
+use crate::script_controller::{CancellationToken, ScriptBackend, ScriptController, ScriptRequest};
+use crate::search::{SearchKind, SearchTarget};
+use crate::selection::{TrackResult, TrackSelection};
+use crate::session::ScriptSession;
+use crate::track::{Artwork, DeadTrack, Track};
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Instance-based entry point of the crate, running every command through its `ScriptBackend`.
+///
+/// A client is built through `AppleMusicClient::builder()`, or with `AppleMusicClient::default()`
+/// to target the local `Music` application through `osascript`.
+#[derive(Clone)]
+pub struct AppleMusicClient {
+    backend: Arc<dyn ScriptBackend>,
+    config: ClientConfig,
+}
+
+impl Default for AppleMusicClient {
+    fn default() -> Self {
+        AppleMusicClient::builder().build()
+    }
+}
+
+impl AppleMusicClient {
+    /// Returns a builder to configure a new client.
+    pub fn builder() -> AppleMusicClientBuilder {
+        AppleMusicClientBuilder::default()
+    }
+
+    /// Creates a new client running its commands through the provided backend.
+    pub fn with_backend<B>(backend: B) -> Self
+    where
+        B: ScriptBackend + 'static,
+    {
+        AppleMusicClient::builder().backend(backend).build()
+    }
+
+    /// Returns the backend used by this client.
+    pub fn backend(&self) -> &dyn ScriptBackend {
+        self.backend.as_ref()
+    }
+
+    /// Returns the name of the application targeted by this client.
+    pub fn app_name(&self) -> &str {
+        &self.config.app_name
+    }
+
+    /// Returns a new Batch, collecting commands to run them all in a single script.
+    pub fn batch(&self) -> Batch<'_> {
+        Batch::new(self, self.app())
+    }
+
+    /// Returns a new query on Library Tracks, filtered inside Apple Music.
+    pub fn tracks(&self) -> TrackQuery<&AppleMusicClient> {
+        TrackQuery::new(self)
+    }
+
+    /// Returns a new query on Playlists, filtered inside Apple Music.
+    pub fn playlists(&self) -> PlaylistQuery<&AppleMusicClient> {
+        PlaylistQuery::new(self)
+    }
+
+    /// Returns a copy of this client whose scripts are aborted after the provided duration.
+    ///
+    /// e.g. `client.with_timeout(Duration::from_secs(60)).get_all_library_tracks()`
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        let mut client = self.clone();
+        client.config.options.timeout = Some(timeout);
+        client
+    }
+
+    /// Returns a copy of this client whose scripts are aborted once the provided token is cancelled.
+    pub fn with_cancellation(&self, token: &CancellationToken) -> Self {
+        let mut client = self.clone();
+        client.config.options.cancellation = Some(token.clone());
+        client
+    }
+
+    /// Returns an up-to-date ApplicationData struct.
+    pub fn get_application_data(&self) -> Result<ApplicationData, Error> {
+        self.call(calls::application_data())
+    }
+
+    /// Returns a lightweight handle on the provided Track, running commands without fetching it.
+    pub fn track<T: Into<TrackId>>(&self, track: T) -> TrackRef<AppleMusicClient> {
+        TrackRef::new(self.clone(), track.into())
+    }
+
+    /// Returns a lightweight handle on the provided Playlist, running commands without fetching it.
+    pub fn playlist<P: Into<PlaylistId>>(&self, playlist: P) -> PlaylistRef<AppleMusicClient> {
+        PlaylistRef::new(self.clone(), playlist.into())
+    }
+
+    /// Returns the Album with the provided name & album artist, running commands on all of its Tracks.
+    pub fn album(&self, name: &str, artist: &str) -> Album<AppleMusicClient> {
+        Album::new(self.clone(), name, artist)
+    }
+
+    /// Returns every Album of the library, grouping Library Tracks by album name & album artist.
+    pub fn get_albums(&self) -> Result<Vec<Album<AppleMusicClient>>, Error> {
+        let tracks = self.get_all_library_partial_tracks(&ALBUM_FIELDS)?;
+        Ok(group_albums(self, tracks))
+    }
+
+    /// Returns a new TrackEditor, collecting changes to the provided Track to apply them in a single script.
+    pub fn edit_track<T: Into<TrackId>>(&self, track: T) -> TrackEditor<AppleMusicClient> {
+        TrackEditor::new(self.clone(), track.into().specifier(&self.app()))
+    }
+
+    /// Returns a new BulkTrackEditor, collecting changes to apply to every provided Track in a single script.
+    pub fn edit_tracks<I, T>(&self, tracks: I) -> BulkTrackEditor<AppleMusicClient>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<TrackId>,
+    {
+        BulkTrackEditor::for_selection(self.clone(), TrackSelection::tracks(&self.app(), tracks))
+    }
+
+    /// Adds the provided files & folders to the library, or to the provided Playlist, and returns the added Tracks.
+    /// Returns an `Error::InvalidArgument` if any path does not exist or is a file of an unsupported format.
+    pub fn add<P: AsRef<Path>>(
+        &self,
+        paths: &[P],
+        to_playlist: Option<PlaylistId>,
+    ) -> Result<Vec<Track>, Error> {
+        self.call(calls::add(paths, to_playlist))
+    }
+
+    /// Looks for and returns the provided Track, by id or persistent ID, if it exists.
+    pub fn get_track<T: Into<TrackId>>(&self, track: T) -> Result<Track, Error> {
+        self.call(calls::track(&track.into()))
+    }
+
+    /// Looks for and returns the provided Playlist, by id or persistent ID, if it exists.
+    pub fn get_playlist<P: Into<PlaylistId>>(&self, playlist: P) -> Result<Playlist, Error> {
+        self.call(calls::playlist(&playlist.into()))
+    }
+
+    /// Looks for and returns the Tracks with the provided ids, in a single script.
+    /// Results are in the same order as `ids`, with `None` for every Track which does not exist.
+    pub fn get_tracks_by_ids(&self, ids: &[i32]) -> Result<Vec<Option<Track>>, Error> {
+        self.call(calls::tracks_by_ids(ids))
+    }
+
+    /// Looks for and returns the Tracks with the provided persistent IDs, in a single script.
+    /// Results are in the same order as `persistent_ids`, with `None` for every Track which does not exist.
+    pub fn get_tracks_by_persistent_ids<S: AsRef<str>>(
+        &self,
+        persistent_ids: &[S],
+    ) -> Result<Vec<Option<Track>>, Error> {
+        self.call(calls::tracks_by_persistent_ids(persistent_ids))
+    }
+
+    /// Scans every file Track of the library in a single script, and returns those whose file does not exist anymore.
+    pub fn find_dead_tracks(&self) -> Result<Vec<DeadTrack>, Error> {
+        self.call(calls::dead_tracks())
+    }
+
+    /// Looks for and returns a Playlist based on provided id, if it exists.
+    pub fn get_playlist_by_id(&self, id: i32) -> Result<Playlist, Error> {
+        self.call(calls::playlist_by_id(id))
+    }
+
+    /// Looks for and returns a Playlist based on provided persistent ID, if it exists.
+    /// Unlike `id`, the persistent ID does not change when Apple Music is restarted.
+    pub fn get_playlist_by_persistent_id(&self, persistent_id: &str) -> Result<Playlist, Error> {
+        self.call(calls::playlist_by_persistent_id(persistent_id))
+    }
+
+    /// Looks for and returns a Track based on provided persistent ID, if it exists.
+    /// Unlike `id`, the persistent ID does not change when Apple Music is restarted.
+    pub fn get_track_by_persistent_id(&self, persistent_id: &str) -> Result<Track, Error> {
+        self.call(calls::track_by_persistent_id(persistent_id))
+    }
+
+    /// Returns currently playing Track, if any.
+    pub fn get_current_track(&self) -> Result<Track, Error> {
+        self.call(calls::current_track())
+    }
+
+    /// Fetches and returns a list of all Library Tracks, `DEFAULT_PAGE_SIZE` Tracks per script.
+    pub fn get_all_library_tracks(&self) -> Result<Vec<Track>, Error> {
+        self.library_track_pages(DEFAULT_PAGE_SIZE).collect_tracks()
+    }
+
+    /// Returns an iterator fetching Library Tracks page by page, `page_size` Tracks per script.
+    pub fn library_track_pages(&self, page_size: usize) -> TrackPages {
+        TrackPages::new(self.clone(), calls::library_tracks(), page_size)
+    }
+
+    /// Fetches and returns all Library Tracks, only holding the provided fields.
+    ///
+    /// e.g. `client.get_all_library_partial_tracks(&[TrackField::Id, TrackField::Name, TrackField::Rating])`
+    pub fn get_all_library_partial_tracks(
+        &self,
+        fields: &[TrackField],
+    ) -> Result<Vec<PartialTrack>, Error> {
+        self.call(calls::page_tracks(calls::library_tracks().fields(fields)))
+    }
+
+    /// Returns an iterator fetching Library Tracks page by page, only holding the provided fields.
+    /// Every field is fetched for the whole collection in a single script, then handed out `page_size` Tracks at a time.
+    pub fn library_partial_track_pages(
+        &self,
+        fields: &[TrackField],
+        page_size: usize,
+    ) -> TrackPages<PartialTrack> {
+        TrackPages::new(
+            self.clone(),
+            calls::library_tracks().fields(fields),
+            page_size,
+        )
+    }
+
+    /// Returns the number of Library Tracks, without fetching them.
+    pub fn count_library_tracks(&self) -> Result<usize, Error> {
+        self.tracks().count()
+    }
+
+    /// Returns the number of Playlists, without fetching them.
+    pub fn count_playlists(&self) -> Result<usize, Error> {
+        self.playlists().count()
+    }
+
+    /// Returns true if the provided Track exists, without fetching it.
+    pub fn track_exists<T: Into<TrackId>>(&self, track: T) -> Result<bool, Error> {
+        self.call(calls::track_exists(&track.into()))
+    }
+
+    /// Returns true if the provided Playlist exists, without fetching it.
+    pub fn playlist_exists<P: Into<PlaylistId>>(&self, playlist: P) -> Result<bool, Error> {
+        self.call(calls::playlist_exists(&playlist.into()))
+    }
+
+    /// Plays the provided Track on AppleMusic player.
+    pub fn play_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        self.execute(&commands::play_track(&self.app(), &track.into()))
+    }
+
+    /// Plays the provided Playlist on AppleMusic player.
+    pub fn play_playlist<P: Into<PlaylistId>>(&self, playlist: P) -> Result<(), Error> {
+        self.execute(&commands::play_playlist(&self.app(), &playlist.into()))
+    }
+
+    /// Mutes / Unmutes AppleMusic player.
+    pub fn set_mute(&self, value: bool) -> Result<(), Error> {
+        self.execute(&commands::set_mute(&self.app(), value))
+    }
+
+    /// Activates / Deactivates Shuffle mode on AppleMusic player.
+    pub fn set_shuffle(&self, value: bool) -> Result<(), Error> {
+        self.execute(&commands::set_shuffle(&self.app(), value))
+    }
+
+    /// Sets Song Repeat mode to provided value.
+    pub fn set_song_repeat_mode(&self, value: SongRepeatMode) -> Result<(), Error> {
+        self.execute(&commands::set_song_repeat_mode(&self.app(), &value))
+    }
+
+    /// Sets Sound Volume to provided value. ( 0 <= value <= 100 )
+    pub fn set_sound_volume(&self, value: i8) -> Result<(), Error> {
+        self.execute(&commands::set_sound_volume(&self.app(), value))
+    }
+
+    /// Tries to convert the provided Track.
+    pub fn convert_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        self.execute(&commands::convert_track(&self.app(), &track.into()))
+    }
+
+    /// Resumes the player if a track is Paused, otherwise Plays a Track from Library.
+    pub fn play(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "play"))
+    }
+
+    /// Pauses the player's currently playing Track.
+    pub fn pause(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "pause"))
+    }
+
+    /// Stops Rewinding / Fast-Forwarding and plays the Track at normal speed.
+    pub fn resume(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "resume"))
+    }
+
+    /// Restart the current Track.
+    pub fn back_track(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "backTrack"))
+    }
+
+    /// Fast-forwards the current Track up until resuming or end of current Track.
+    pub fn fast_forward(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "fastForward"))
+    }
+
+    /// Skips current Track and plays next one.
+    pub fn next_track(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "nextTrack"))
+    }
+
+    /// Plays if Player is currently Paused, Pauses if Player is currently Playing.
+    pub fn playpause(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "playpause"))
+    }
+
+    /// Goes back to previous Track and plays it.
+    pub fn previous_track(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "previousTrack"))
+    }
+
+    /// Rewinds current Track up until resuming or start of Track.
+    pub fn rewind(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "rewind"))
+    }
+
+    /// Stops player, removing enqueued Tracks and currently playing Track.
+    pub fn stop(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "stop"))
+    }
+
+    /// Fully Quits Apple Music.
+    pub fn quit(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "quit"))
+    }
+
+    /// Opens Apple Music app.
+    pub fn run(&self) -> Result<(), Error> {
+        self.execute(&commands::call(&self.app(), "run"))
+    }
+
+    /// Returns a list of all artworks of the provided Track, with their raw_data.
+    pub fn fetch_track_artworks<T: Into<TrackId>>(&self, track: T) -> Result<Vec<Artwork>, Error> {
+        self.call(calls::artworks(&track.into()))
+    }
+
+    /// Adds the provided image as the last artwork of the provided Track.
+    /// Returns an `Error::InvalidArgument` if the image is not a PNG, JPEG or TIFF image.
+    pub fn add_track_artwork<T: Into<TrackId>>(
+        &self,
+        track: T,
+        image: &ArtworkImage,
+    ) -> Result<(), Error> {
+        let tracks = commands::tracks(&self.app(), &track.into());
+        self.call(calls::add_artwork(&self.app(), &tracks, image))
+    }
+
+    /// Replaces the first artwork of the provided Track with the provided image, or adds it if there is none.
+    /// Returns an `Error::InvalidArgument` if the image is not a PNG, JPEG or TIFF image.
+    pub fn replace_track_artwork<T: Into<TrackId>>(
+        &self,
+        track: T,
+        image: &ArtworkImage,
+    ) -> Result<(), Error> {
+        let tracks = commands::tracks(&self.app(), &track.into());
+        self.call(calls::replace_artwork(&self.app(), &tracks, image))
+    }
+
+    /// Deletes every artwork of the provided Track.
+    pub fn remove_track_artworks<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        let tracks = commands::tracks(&self.app(), &track.into());
+        self.execute(&commands::remove_artworks(&tracks))
+    }
+
+    /// Updates the provided file Track from the metadata of its file, e.g. after its tags were rewritten.
+    /// Returns an `Error::NotFileTrack` if the Track is not a file Track.
+    pub fn refresh_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        let results = self.refresh(TrackSelection::tracks(&self.app(), [track]))?;
+
+        results
+            .into_iter()
+            .next()
+            .map_or(Ok(()), |refresh| refresh.result)
+    }
+
+    /// Updates every provided file Track from the metadata of its file, in a single script, and returns their results.
+    /// Tracks which are not file Tracks fail with an `Error::NotFileTrack`, without preventing the others from being refreshed.
+    pub fn refresh_tracks<I, T>(&self, tracks: I) -> Result<Vec<TrackResult>, Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<TrackId>,
+    {
+        self.refresh(TrackSelection::tracks(&self.app(), tracks))
+    }
+
+    /// Reveals and selects provided Track in Apple Music.
+    pub fn reveal_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        self.execute(&commands::reveal_track(&self.app(), &track.into()))
+    }
+
+    /// Triggers a download on Apple Music Player for the provided Track.
+    pub fn download_track<T: Into<TrackId>>(&self, track: T) -> Result<(), Error> {
+        self.execute(&commands::download_track(&self.app(), &track.into()))
+    }
+
+    /// Favorites / "Unfavorites" provided Track.
+    pub fn set_track_favorited<T: Into<TrackId>>(
+        &self,
+        track: T,
+        value: bool,
+    ) -> Result<(), Error> {
+        self.execute(&commands::set_track_favorited(
+            &self.app(),
+            &track.into(),
+            value,
+        ))
+    }
+
+    /// Dislikes / "Undislikes" provided Track.
+    pub fn set_track_disliked<T: Into<TrackId>>(&self, track: T, value: bool) -> Result<(), Error> {
+        self.execute(&commands::set_track_disliked(
+            &self.app(),
+            &track.into(),
+            value,
+        ))
+    }
+
+    /// Fetches and returns the Tracks of provided Playlist, `DEFAULT_PAGE_SIZE` Tracks per script.
+    pub fn fetch_playlist_tracks<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+    ) -> Result<Vec<Track>, Error> {
+        self.playlist_track_pages(playlist, DEFAULT_PAGE_SIZE)
+            .collect_tracks()
+    }
+
+    /// Returns an iterator fetching the Tracks of provided Playlist page by page, `page_size` Tracks per script.
+    pub fn playlist_track_pages<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+        page_size: usize,
+    ) -> TrackPages {
+        TrackPages::new(
+            self.clone(),
+            calls::playlist_tracks(&playlist.into()),
+            page_size,
+        )
+    }
+
+    /// Fetches and returns the Tracks of provided Playlist, only holding the provided fields.
+    pub fn fetch_playlist_partial_tracks<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+        fields: &[TrackField],
+    ) -> Result<Vec<PartialTrack>, Error> {
+        let request = calls::playlist_tracks(&playlist.into()).fields(fields);
+        self.call(calls::page_tracks(request))
+    }
+
+    /// Returns an iterator fetching the Tracks of provided Playlist page by page, only holding the provided fields.
+    /// Every field is fetched for the whole collection in a single script, then handed out `page_size` Tracks at a time.
+    pub fn playlist_partial_track_pages<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+        fields: &[TrackField],
+        page_size: usize,
+    ) -> TrackPages<PartialTrack> {
+        TrackPages::new(
+            self.clone(),
+            calls::playlist_tracks(&playlist.into()).fields(fields),
+            page_size,
+        )
+    }
+
+    /// Searches the whole library for Tracks matching provided query, as Apple Music's search field does.
+    pub fn search(&self, query: &str, kind: SearchKind) -> Result<Vec<Track>, Error> {
+        self.search_in(SearchTarget::Library, query, kind)
+    }
+
+    /// Searches inside provided Playlist or source for Tracks matching provided query.
+    pub fn search_in<S: Into<SearchTarget>>(
+        &self,
+        target: S,
+        query: &str,
+        kind: SearchKind,
+    ) -> Result<Vec<Track>, Error> {
+        self.call(calls::search(target.into(), query, kind))
+    }
+
+    /// Searches inside provided Playlist for Tracks containing provided query and returns them.
+    pub fn search_in_playlist<P: Into<PlaylistId>>(
+        &self,
+        playlist: P,
+        query: &str,
+    ) -> Result<Vec<Track>, Error> {
+        self.call(calls::search_in_playlist(&playlist.into(), query))
+    }
+
+    /// Reveals and selects provided Playlist in Apple Music player.
+    pub fn reveal_playlist<P: Into<PlaylistId>>(&self, playlist: P) -> Result<(), Error> {
+        self.execute(&commands::reveal_playlist(&self.app(), &playlist.into()))
+    }
+
+    /// Triggers a download on Apple Music Player for the provided Playlist.
+    pub fn download_playlist<P: Into<PlaylistId>>(&self, playlist: P) -> Result<(), Error> {
+        self.execute(&commands::download_playlist(&self.app(), &playlist.into()))
+    }
+
+    /// Returns the specifier of the targeted application, e.g. `Application("Music")`.
+    pub(crate) fn app(&self) -> Jxa {
+        self.config.app()
+    }
+
+    /// Runs the call, blocking until its script is over.
+    pub(crate) fn call<T>(&self, call: Call<T>) -> Result<T, Error> {
+        let result = match call {
+            Call::Done(result) => result,
+            Call::Request(request, output) => {
+                let request = self.config.prepare(request);
+                output(self.backend.execute_request(&request, &self.config.options))
+            }
+            Call::Script(script, output) => {
+                self.config.log_script(&script);
+                output(self.backend.execute(&script, None, &self.config.options))
+            }
+        };
+
+        self.config.report(result)
+    }
+
+    pub(crate) fn execute(&self, cmd: &str) -> Result<(), Error> {
+        self.call(Call::script(cmd))
+    }
+
+    pub(crate) fn refresh(&self, tracks: TrackSelection) -> Result<Vec<TrackResult>, Error> {
+        self.call(calls::refresh(&self.app(), tracks))
+    }
+
+    /// Executes a raw script and returns its output.
+    pub(crate) fn execute_script(&self, cmd: &str) -> Result<String, Error> {
+        self.call(Call::output(cmd))
+    }
+
+    pub(crate) fn execute_request<T>(&self, request: &ScriptRequest) -> Result<T, Error>
+    where
+        T: DeserializeOwned + 'static,
+    {
+        self.call(Call::request(request.clone()))
+    }
+}
+
+/// Builder of an `AppleMusicClient`.
+pub struct AppleMusicClientBuilder {
+    app_name: String,
+    osascript_path: PathBuf,
+    timeout: Option<Duration>,
+    logging: bool,
+    session: bool,
+    backend: Option<Arc<dyn ScriptBackend>>,
+}
+
+impl Default for AppleMusicClientBuilder {
+    fn default() -> Self {
+        AppleMusicClientBuilder {
+            app_name: String::from("Music"),
+            osascript_path: PathBuf::from("osascript"),
+            timeout: None,
+            logging: true,
+            session: false,
+            backend: None,
+        }
+    }
+}
+
+impl AppleMusicClientBuilder {
+    /// Sets the name of the targeted application. Defaults to `Music`.
+    pub fn app_name(mut self, app_name: &str) -> Self {
+        self.app_name = app_name.into();
+        self
+    }
+
+    /// Sets the path of the `osascript` executable used by the default backend.
+    pub fn osascript_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.osascript_path = path.as_ref().to_path_buf();
+        self
+    }
+
+    /// Sets the maximum duration of every script. No timeout by default.
+    /// It can be overridden per call through `AppleMusicClient::with_timeout`.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Enables / Disables logging of executed scripts and failures. Enabled by default.
+    pub fn logging(mut self, value: bool) -> Self {
+        self.logging = value;
+        self
+    }
+
+    /// Keeps a single `osascript` process alive for all commands, through a `ScriptSession`.
+    /// Disabled by default.
+    pub fn persistent_session(mut self, value: bool) -> Self {
+        self.session = value;
+        self
+    }
+
+    /// Sets a custom backend. `osascript_path` and `persistent_session` are then ignored.
+    pub fn backend<B>(mut self, backend: B) -> Self
+    where
+        B: ScriptBackend + 'static,
+    {
+        self.backend = Some(Arc::new(backend));
+        self
+    }
+
+    /// Builds the configured `AppleMusicClient`.
+    pub fn build(self) -> AppleMusicClient {
+        let backend = match self.backend {
+            Some(backend) => backend,
+            None if self.session => {
+                Arc::new(ScriptSession::with_osascript_path(self.osascript_path))
+            }
+            None => Arc::new(ScriptController::default().osascript_path(self.osascript_path)),
+        };
+
+        AppleMusicClient {
+            backend,
+            config: ClientConfig::new(self.app_name, self.logging, self.timeout),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ScriptError;
+    use crate::mock::{RecordingBackend, Sent};
+    use serde_json::json;
+
+    fn client() -> (AppleMusicClient, RecordingBackend) {
+        let backend = RecordingBackend::default();
+        let client = AppleMusicClient::builder()
+            .app_name("Music Beta")
+            .backend(backend.clone())
+            .build();
+        (client, backend)
+    }
+
+    fn not_found() -> Error {
+        ScriptError::from_stderr("Error: Can't get object. (-1728)", Some(1), "script", None).into()
+    }
+
+    #[test]
+    fn sends_typed_requests() {
+        let (client, backend) = client();
+        backend.respond("true").respond("[null]");
+
+        assert!(client.track_exists(TrackId::persistent("ABC")).unwrap());
+        assert!(client.get_tracks_by_ids(&[42]).unwrap()[0].is_none());
+
+        assert_eq!(
+            backend.sent(),
+            [
+                Sent::Request(
+                    json!({"param_type": "trackExists", "app": "Music Beta", "persistent_id": "ABC"})
+                ),
+                Sent::Request(
+                    json!({"param_type": "tracksByIds", "app": "Music Beta", "ids": [42]})
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn sends_commands_to_the_configured_application() {
+        let (client, backend) = client();
+
+        client.set_mute(true).unwrap();
+        client.play_track(42).unwrap();
+
+        assert_eq!(
+            backend.sent(),
+            [
+                Sent::Script(String::from(r#"Application("Music Beta").mute = true"#)),
+                Sent::Script(String::from(
+                    r#"Application("Music Beta").play(Application("Music Beta").tracks.byId(42))"#
+                )),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_scripts_of_empty_lists() {
+        let (client, backend) = client();
+
+        assert!(client.get_tracks_by_ids(&[]).unwrap().is_empty());
+        assert!(client
+            .get_tracks_by_persistent_ids::<&str>(&[])
+            .unwrap()
+            .is_empty());
+        assert!(client.add::<&str>(&[], None).unwrap().is_empty());
+        assert!(client.refresh_tracks(Vec::<i32>::new()).unwrap().is_empty());
+
+        assert!(backend.sent().is_empty());
+    }
+
+    #[test]
+    fn reports_missing_current_track_as_not_playing() {
+        let (client, backend) = client();
+        backend.fail(not_found());
+
+        assert!(matches!(client.get_current_track(), Err(Error::NotPlaying)));
+    }
+
+    #[test]
+    fn returns_backend_failures() {
+        let (client, backend) = client();
+        backend.fail(not_found()).fail(Error::Cancelled);
+
+        assert!(matches!(
+            client.get_track(42),
+            Err(Error::ObjectNotFound(_))
+        ));
+        assert!(matches!(client.play(), Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn reports_invalid_output() {
+        let (client, backend) = client();
+        backend.respond(r#"{"not": "a bool"}"#);
+
+        assert!(matches!(
+            client.track_exists(42),
+            Err(Error::Deserialization { .. })
+        ));
+    }
+
+    #[test]
+    fn passes_timeouts_to_the_backend() {
+        let (client, backend) = client();
+        let timeout = Duration::from_secs(5);
+
+        client.with_timeout(timeout).play().unwrap();
+        client.play().unwrap();
+
+        assert_eq!(backend.timeouts(), [Some(timeout), None]);
+    }
+}
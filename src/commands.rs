@@ -0,0 +1,187 @@
+//! JXA commands shared by every client, built against the specifier of the targeted application.
+use crate::apple_music::SongRepeatMode;
+use crate::id::{PlaylistId, TrackId};
+use crate::jxa::Jxa;
+use std::path::Path;
+
+/// Calls a method without arguments on the application, e.g. `play()` or `nextTrack()`.
+pub(crate) fn call(app: &Jxa, method: &str) -> String {
+    app.call(method, &[]).into()
+}
+
+pub(crate) fn track(app: &Jxa, id: &TrackId) -> Jxa {
+    id.specifier(app)
+}
+
+pub(crate) fn playlist(app: &Jxa, id: &PlaylistId) -> Jxa {
+    id.specifier(app)
+}
+
+pub(crate) fn play_track(app: &Jxa, id: &TrackId) -> String {
+    app.call("play", &[track(app, id)]).into()
+}
+
+pub(crate) fn play_playlist(app: &Jxa, id: &PlaylistId) -> String {
+    app.call("play", &[playlist(app, id)]).into()
+}
+
+pub(crate) fn set_mute(app: &Jxa, value: bool) -> String {
+    app.property("mute").set(value).into()
+}
+
+pub(crate) fn set_shuffle(app: &Jxa, value: bool) -> String {
+    app.property("shuffleEnabled").set(value).into()
+}
+
+pub(crate) fn set_song_repeat_mode(app: &Jxa, value: &SongRepeatMode) -> String {
+    app.property("songRepeat").set(value.to_string()).into()
+}
+
+pub(crate) fn set_sound_volume(app: &Jxa, value: i8) -> String {
+    app.property("soundVolume").set(value).into()
+}
+
+pub(crate) fn convert_track(app: &Jxa, id: &TrackId) -> String {
+    app.call("convert", &[track(app, id)]).into()
+}
+
+pub(crate) fn reveal_track(app: &Jxa, id: &TrackId) -> String {
+    app.call("reveal", &[track(app, id)]).into()
+}
+
+pub(crate) fn download_track(app: &Jxa, id: &TrackId) -> String {
+    app.call("download", &[track(app, id)]).into()
+}
+
+pub(crate) fn set_track_favorited(app: &Jxa, id: &TrackId, value: bool) -> String {
+    track(app, id).property("favorited").set(value).into()
+}
+
+pub(crate) fn set_track_disliked(app: &Jxa, id: &TrackId, value: bool) -> String {
+    track(app, id).property("disliked").set(value).into()
+}
+
+pub(crate) fn reveal_playlist(app: &Jxa, id: &PlaylistId) -> String {
+    app.call("reveal", &[playlist(app, id)]).into()
+}
+
+pub(crate) fn download_playlist(app: &Jxa, id: &PlaylistId) -> String {
+    app.call("download", &[playlist(app, id)]).into()
+}
+
+/// Statements refreshing the `track` variable from its file, throwing a failure holding its kind if it is not a file Track.
+pub(crate) fn refresh(app: &Jxa) -> Vec<String> {
+    vec![
+        String::from("const kind = track.class();"),
+        String::from("if (kind !== \"fileTrack\") { throw {message: \"Only file tracks can be refreshed\", kind: kind}; }"),
+        format!("{};", app.call("refresh", &[Jxa::raw("track")])),
+    ]
+}
+
+/// Returns an array holding the single provided Track, e.g. `[Application("Music").tracks.byId(42)]`.
+pub(crate) fn tracks(app: &Jxa, id: &TrackId) -> Jxa {
+    Jxa::raw(&format!("[{}]", track(app, id)))
+}
+
+/// Adds the image at the provided path as a new artwork of every provided Track.
+pub(crate) fn add_artwork(app: &Jxa, tracks: &Jxa, image: &Path) -> String {
+    for_each_track(tracks, Some(image), &make_artwork(app))
+}
+
+/// Sets the image at the provided path as the first artwork of every provided Track,
+/// or adds it to the Tracks without any artwork.
+pub(crate) fn replace_artwork(app: &Jxa, tracks: &Jxa, image: &Path) -> String {
+    let statement = format!(
+        "if (track.artworks.length === 0) {{ {} }} else {{ track.artworks[0].data = image; }}",
+        make_artwork(app)
+    );
+
+    for_each_track(tracks, Some(image), &statement)
+}
+
+/// Deletes every artwork of every provided Track.
+pub(crate) fn remove_artworks(tracks: &Jxa) -> String {
+    for_each_track(
+        tracks,
+        None,
+        "for (let i = track.artworks.length - 1; i >= 0; i--) { track.artworks[i].delete(); }",
+    )
+}
+
+/// Creates a new artwork of `track` from `image`, through the `make` command of the application
+/// (`make new artwork at track`): assigning past the last artwork is not a documented way to create one.
+fn make_artwork(app: &Jxa) -> String {
+    let make = app.call(
+        "make",
+        &[Jxa::raw(
+            "{new: \"artwork\", at: track, withProperties: {data: image}}",
+        )],
+    );
+
+    format!("{};", make)
+}
+
+/// Runs the statement on every Track of the provided array, with the image at the provided path loaded as `image`.
+fn for_each_track(tracks: &Jxa, image: Option<&Path>, statement: &str) -> String {
+    let image = match image {
+        Some(path) => format!(
+            "    ObjC.import(\"AppKit\");\n    const image = $.NSImage.alloc.initWithContentsOfFile({});\n    if (image.isNil()) {{ throw new Error(\"Could not read artwork image\"); }}\n",
+            Jxa::json(&path.to_string_lossy().into())
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "(() => {{\n{}    for (const track of {}) {{ {} }}\n}})()",
+        image, tracks, statement
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOAD_IMAGE: &str = "(() => {\n    ObjC.import(\"AppKit\");\n    const image = $.NSImage.alloc.initWithContentsOfFile(\"/tmp/cover \\\"1\\\".png\");\n    if (image.isNil()) { throw new Error(\"Could not read artwork image\"); }\n";
+
+    fn app() -> Jxa {
+        Jxa::application("Music")
+    }
+
+    fn tracks() -> Jxa {
+        super::tracks(&app(), &TrackId::Id(42))
+    }
+
+    fn image() -> &'static Path {
+        Path::new("/tmp/cover \"1\".png")
+    }
+
+    #[test]
+    fn add_artwork_makes_a_new_artwork() {
+        assert_eq!(
+            add_artwork(&app(), &tracks(), image()),
+            format!(
+                "{}    for (const track of [Application(\"Music\").tracks.byId(42)]) {{ Application(\"Music\").make({{new: \"artwork\", at: track, withProperties: {{data: image}}}}); }}\n}})()",
+                LOAD_IMAGE
+            )
+        );
+    }
+
+    #[test]
+    fn replace_artwork_makes_an_artwork_if_there_is_none() {
+        assert_eq!(
+            replace_artwork(&app(), &tracks(), image()),
+            format!(
+                "{}    for (const track of [Application(\"Music\").tracks.byId(42)]) {{ if (track.artworks.length === 0) {{ Application(\"Music\").make({{new: \"artwork\", at: track, withProperties: {{data: image}}}}); }} else {{ track.artworks[0].data = image; }} }}\n}})()",
+                LOAD_IMAGE
+            )
+        );
+    }
+
+    #[test]
+    fn remove_artworks_deletes_from_the_last() {
+        assert_eq!(
+            remove_artworks(&tracks()),
+            "(() => {\n    for (const track of [Application(\"Music\").tracks.byId(42)]) { for (let i = track.artworks.length - 1; i >= 0; i--) { track.artworks[i].delete(); } }\n})()"
+        );
+    }
+}
@@ -0,0 +1,551 @@
+#[cfg(feature = "async")]
+use crate::async_client::AsyncAppleMusicClient;
+use crate::client::AppleMusicClient;
+use crate::error::Error;
+use crate::jxa::Jxa;
+use crate::selection::{TrackResult, TrackSelection};
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt::Display;
+
+/// Collection of changes to the writable properties of a Track, applied in a single script.
+///
+/// Every value is validated before anything is sent to Apple Music:
+/// ```rust,ignore
+/// client
+///     .edit_track(&track)
+///     .name("One More Time")
+///     .artist("Daft Punk")
+///     .rating(80)
+///     .year(2000)
+///     .apply()?;
+/// ```
+pub struct TrackEditor<C = AppleMusicClient, S = Jxa> {
+    client: C,
+    target: S,
+    changes: Vec<(&'static str, Value)>,
+    invalid: Option<String>,
+}
+
+/// `TrackEditor` applying the same changes to many Tracks in a single script, e.g. a whole album:
+/// ```rust,ignore
+/// let results = client
+///     .tracks()
+///     .filter(TrackField::Album, Operator::Equals, "Discovery")
+///     .edit()
+///     .album_artist("Daft Punk")
+///     .genre("House")
+///     .apply()?;
+/// ```
+/// Every Track gets its own result, and a failure does not prevent the other Tracks from being edited.
+pub type BulkTrackEditor<C = AppleMusicClient> = TrackEditor<C, TrackSelection>;
+
+impl<C, S> TrackEditor<C, S> {
+    /// Returns the number of properties changed by the editor.
+    pub fn len(&self) -> usize {
+        self.changes.len()
+    }
+
+    /// Returns true if the editor does not change any property.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Sets the name of the track.
+    pub fn name(self, value: &str) -> Self {
+        self.set("name", value)
+    }
+
+    /// Sets the artist of the track.
+    pub fn artist(self, value: &str) -> Self {
+        self.set("artist", value)
+    }
+
+    /// Sets the album name of the track.
+    pub fn album(self, value: &str) -> Self {
+        self.set("album", value)
+    }
+
+    /// Sets the album artist of the track.
+    pub fn album_artist(self, value: &str) -> Self {
+        self.set("albumArtist", value)
+    }
+
+    /// Sets the composer of the track.
+    pub fn composer(self, value: &str) -> Self {
+        self.set("composer", value)
+    }
+
+    /// Sets the music/audio genre (category) of the track.
+    pub fn genre(self, value: &str) -> Self {
+        self.set("genre", value)
+    }
+
+    /// Sets the grouping (piece) of the track.
+    pub fn grouping(self, value: &str) -> Self {
+        self.set("grouping", value)
+    }
+
+    /// Sets the freeform notes about the track.
+    pub fn comment(self, value: &str) -> Self {
+        self.set("comment", value)
+    }
+
+    /// Sets the lyrics of the track.
+    pub fn lyrics(self, value: &str) -> Self {
+        self.set("lyrics", value)
+    }
+
+    /// Sets the description of the track.
+    pub fn description(self, value: &str) -> Self {
+        self.set("description", value)
+    }
+
+    /// Sets the long description of the track.
+    pub fn long_description(self, value: &str) -> Self {
+        self.set("longDescription", value)
+    }
+
+    /// Sets the category of the track.
+    pub fn category(self, value: &str) -> Self {
+        self.set("category", value)
+    }
+
+    /// Sets the name of the EQ preset of the track.
+    pub fn eq(self, value: &str) -> Self {
+        self.set("eq", value)
+    }
+
+    /// Sets the show name of the track.
+    pub fn show(self, value: &str) -> Self {
+        self.set("show", value)
+    }
+
+    /// Sets the work name of the track.
+    pub fn work(self, value: &str) -> Self {
+        self.set("work", value)
+    }
+
+    /// Sets the movement name of the track.
+    pub fn movement(self, value: &str) -> Self {
+        self.set("movement", value)
+    }
+
+    /// Sets the override string to use for the track when sorting by name.
+    pub fn sort_name(self, value: &str) -> Self {
+        self.set("sortName", value)
+    }
+
+    /// Sets the override string to use for the track when sorting by artist.
+    pub fn sort_artist(self, value: &str) -> Self {
+        self.set("sortArtist", value)
+    }
+
+    /// Sets the override string to use for the track when sorting by album.
+    pub fn sort_album(self, value: &str) -> Self {
+        self.set("sortAlbum", value)
+    }
+
+    /// Sets the override string to use for the track when sorting by album artist.
+    pub fn sort_album_artist(self, value: &str) -> Self {
+        self.set("sortAlbumArtist", value)
+    }
+
+    /// Sets the override string to use for the track when sorting by composer.
+    pub fn sort_composer(self, value: &str) -> Self {
+        self.set("sortComposer", value)
+    }
+
+    /// Sets the override string to use for the track when sorting by show name.
+    pub fn sort_show(self, value: &str) -> Self {
+        self.set("sortShow", value)
+    }
+
+    /// Sets the rating of the track (0 to 100).
+    pub fn rating(self, value: i32) -> Self {
+        self.check("rating", value, 0, Some(100))
+    }
+
+    /// Sets the rating of the album for the track (0 to 100).
+    pub fn album_rating(self, value: i32) -> Self {
+        self.check("albumRating", value, 0, Some(100))
+    }
+
+    /// Sets the tempo of the track in beats per minute.
+    pub fn bpm(self, value: i32) -> Self {
+        self.check("bpm", value, 0, None)
+    }
+
+    /// Sets the year the track was recorded/released.
+    pub fn year(self, value: i32) -> Self {
+        self.check("year", value, 0, None)
+    }
+
+    /// Sets the index of the track on the source album.
+    pub fn track_number(self, value: i32) -> Self {
+        self.check("trackNumber", value, 0, None)
+    }
+
+    /// Sets the total number of tracks on the source album.
+    pub fn track_count(self, value: i32) -> Self {
+        self.check("trackCount", value, 0, None)
+    }
+
+    /// Sets the index of the disc containing the track on the source album.
+    pub fn disc_number(self, value: i32) -> Self {
+        self.check("discNumber", value, 0, None)
+    }
+
+    /// Sets the total number of discs in the source album.
+    pub fn disc_count(self, value: i32) -> Self {
+        self.check("discCount", value, 0, None)
+    }
+
+    /// Sets the index of the movement in the work.
+    pub fn movement_number(self, value: i32) -> Self {
+        self.check("movementNumber", value, 0, None)
+    }
+
+    /// Sets the total number of movements in the work.
+    pub fn movement_count(self, value: i32) -> Self {
+        self.check("movementCount", value, 0, None)
+    }
+
+    /// Sets the episode number of the track.
+    pub fn episode_number(self, value: i32) -> Self {
+        self.check("episodeNumber", value, 0, None)
+    }
+
+    /// Sets the season number of the track.
+    pub fn season_number(self, value: i32) -> Self {
+        self.check("seasonNumber", value, 0, None)
+    }
+
+    /// Sets the number of times the track has been played.
+    pub fn played_count(self, value: i32) -> Self {
+        self.check("playedCount", value, 0, None)
+    }
+
+    /// Sets the number of times the track has been skipped.
+    pub fn skipped_count(self, value: i32) -> Self {
+        self.check("skippedCount", value, 0, None)
+    }
+
+    /// Sets the relative volume adjustment of the track (-100 to 100).
+    pub fn volume_adjustment(self, value: i32) -> Self {
+        self.check("volumeAdjustment", value, -100, Some(100))
+    }
+
+    /// Sets the start time of the track in seconds.
+    pub fn start(self, value: f64) -> Self {
+        self.check_seconds("start", value)
+    }
+
+    /// Sets the stop time of the track in seconds.
+    pub fn finish(self, value: f64) -> Self {
+        self.check_seconds("finish", value)
+    }
+
+    /// Sets the bookmark time of the track in seconds.
+    pub fn bookmark(self, value: f64) -> Self {
+        self.check_seconds("bookmark", value)
+    }
+
+    /// Checks / Unchecks the track for playback.
+    pub fn enabled(self, value: bool) -> Self {
+        self.set("enabled", value)
+    }
+
+    /// Includes / Excludes the track when shuffling.
+    pub fn shufflable(self, value: bool) -> Self {
+        self.set("shufflable", value)
+    }
+
+    /// Remembers / Forgets the playback position of the track.
+    pub fn bookmarkable(self, value: bool) -> Self {
+        self.set("bookmarkable", value)
+    }
+
+    /// Marks / Unmarks the track as from a compilation album.
+    pub fn compilation(self, value: bool) -> Self {
+        self.set("compilation", value)
+    }
+
+    /// Marks / Unmarks the track as from a gapless album.
+    pub fn gapless(self, value: bool) -> Self {
+        self.set("gapless", value)
+    }
+
+    /// Marks / Unmarks the track as unplayed.
+    pub fn unplayed(self, value: bool) -> Self {
+        self.set("unplayed", value)
+    }
+
+    /// Favorites / "Unfavorites" the track.
+    pub fn favorited(self, value: bool) -> Self {
+        self.set("favorited", value)
+    }
+
+    /// Dislikes / "Undislikes" the track.
+    pub fn disliked(self, value: bool) -> Self {
+        self.set("disliked", value)
+    }
+
+    /// Favorites / "Unfavorites" the album of the track.
+    pub fn album_favorited(self, value: bool) -> Self {
+        self.set("albumFavorited", value)
+    }
+
+    /// Dislikes / "Undislikes" the album of the track.
+    pub fn album_disliked(self, value: bool) -> Self {
+        self.set("albumDisliked", value)
+    }
+
+    /// Returns an `Error::InvalidArgument` if any value is out of range.
+    /// Fails the editor with the provided reason, if any, e.g. when its Tracks are matched by an invalid query.
+    pub(crate) fn invalidate(mut self, invalid: Option<String>) -> Self {
+        if let Some(invalid) = invalid {
+            self.invalid.get_or_insert(invalid);
+        }
+        self
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(invalid) = &self.invalid {
+            return Err(Error::InvalidArgument(invalid.clone()));
+        }
+
+        if let (Some(start), Some(finish)) = (self.value("start"), self.value("finish")) {
+            if start.as_f64() > finish.as_f64() {
+                return Err(Error::InvalidArgument(format!(
+                    "start ({}) must not be after finish ({})",
+                    start, finish
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the statements applying every change to the `track` variable.
+    fn statements(&self) -> Vec<String> {
+        self.changes
+            .iter()
+            .map(|(property, value)| {
+                format!(
+                    "{};",
+                    Jxa::raw("track")
+                        .property(property)
+                        .assign(&Jxa::json(value))
+                )
+            })
+            .collect()
+    }
+
+    fn value(&self, property: &str) -> Option<&Value> {
+        self.changes
+            .iter()
+            .find(|(name, _)| *name == property)
+            .map(|(_, value)| value)
+    }
+
+    fn set<T: Serialize>(mut self, property: &'static str, value: T) -> Self {
+        match serde_json::to_value(value) {
+            Ok(value) => {
+                self.changes.retain(|(name, _)| *name != property);
+                self.changes.push((property, value));
+            }
+            Err(err) => {
+                self.invalid
+                    .get_or_insert(format!("{} is not valid JSON: {}", property, err));
+            }
+        }
+        self
+    }
+
+    fn check_seconds(mut self, property: &'static str, value: f64) -> Self {
+        if !value.is_finite() {
+            self.invalid.get_or_insert(format!(
+                "{} must be a finite number, got {}",
+                property, value
+            ));
+            return self;
+        }
+
+        self.check(property, value, 0.0, None)
+    }
+
+    fn check<T>(mut self, property: &'static str, value: T, min: T, max: Option<T>) -> Self
+    where
+        T: Serialize + PartialOrd + Display + Copy,
+    {
+        let invalid = match max {
+            _ if value < min => Some(format!(
+                "{} must be at least {}, got {}",
+                property, min, value
+            )),
+            Some(max) if value > max => Some(format!(
+                "{} must be between {} and {}, got {}",
+                property, min, max, value
+            )),
+            _ => None,
+        };
+
+        match invalid {
+            Some(invalid) => {
+                self.invalid.get_or_insert(invalid);
+                self
+            }
+            None => self.set(property, value),
+        }
+    }
+}
+
+impl<C> TrackEditor<C> {
+    pub(crate) fn new(client: C, track: Jxa) -> Self {
+        TrackEditor {
+            client,
+            target: track,
+            changes: Vec::new(),
+            invalid: None,
+        }
+    }
+
+    /// Returns the single script applying every change, or an `Error::InvalidArgument`
+    /// if any value is out of range.
+    pub fn to_script(&self) -> Result<String, Error> {
+        self.validate()?;
+
+        let statements: String = self
+            .statements()
+            .iter()
+            .map(|statement| format!("    {}\n", statement))
+            .collect();
+
+        Ok(format!(
+            "(() => {{\n    const track = {};\n{}}})()",
+            self.target, statements
+        ))
+    }
+}
+
+impl<C> BulkTrackEditor<C> {
+    pub(crate) fn for_selection(client: C, tracks: TrackSelection) -> Self {
+        TrackEditor {
+            client,
+            target: tracks,
+            changes: Vec::new(),
+            invalid: None,
+        }
+    }
+
+    /// Returns the single script applying every change to every Track, or an `Error::InvalidArgument`
+    /// if any value is out of range.
+    pub fn to_script(&self) -> Result<String, Error> {
+        self.validate()?;
+        Ok(self.target.script(&self.statements()))
+    }
+}
+
+impl TrackEditor<AppleMusicClient> {
+    /// Validates and applies every change in a single script.
+    pub fn apply(self) -> Result<(), Error> {
+        if self.changes.is_empty() && self.invalid.is_none() {
+            return Ok(());
+        }
+
+        let script = self.to_script()?;
+        self.client.execute_script(&script).map(|_| ())
+    }
+}
+
+impl BulkTrackEditor<AppleMusicClient> {
+    /// Validates and applies every change to every Track in a single script, and returns their results.
+    /// Fails as a whole only if a value is invalid, or if the script itself could not be run.
+    pub fn apply(self) -> Result<Vec<TrackResult>, Error> {
+        let script = self.to_script()?;
+
+        if self.target.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let output = self.client.execute_script(&script)?;
+        self.target.results(&script, &output)
+    }
+}
+
+#[cfg(feature = "async")]
+impl TrackEditor<AsyncAppleMusicClient> {
+    /// Validates and applies every change in a single script.
+    pub async fn apply(self) -> Result<(), Error> {
+        if self.changes.is_empty() && self.invalid.is_none() {
+            return Ok(());
+        }
+
+        let script = self.to_script()?;
+        self.client.execute(&script).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl BulkTrackEditor<AsyncAppleMusicClient> {
+    /// Validates and applies every change to every Track in a single script, and returns their results.
+    /// Fails as a whole only if a value is invalid, or if the script itself could not be run.
+    pub async fn apply(self) -> Result<Vec<TrackResult>, Error> {
+        let script = self.to_script()?;
+
+        if self.target.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let output = self.client.execute_script(&script).await?;
+        self.target.results(&script, &output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn editor() -> TrackEditor<()> {
+        TrackEditor::new((), Jxa::raw("track"))
+    }
+
+    #[test]
+    fn rejects_non_finite_times() {
+        for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            assert!(matches!(
+                editor().start(value).to_script(),
+                Err(Error::InvalidArgument(_))
+            ));
+            assert!(matches!(
+                editor().finish(value).to_script(),
+                Err(Error::InvalidArgument(_))
+            ));
+            assert!(matches!(
+                editor().bookmark(value).to_script(),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn accepts_finite_times() {
+        let script = editor().start(1.5).finish(30.0).bookmark(0.0).to_script();
+        assert!(script.is_ok());
+    }
+
+    #[test]
+    fn sets_bookmarks_beyond_a_byte() {
+        let script = editor().bookmark(754.25).to_script().unwrap();
+        assert!(script.contains("track.bookmark = 754.25;"), "{}", script);
+    }
+
+    #[test]
+    fn rejects_start_after_finish() {
+        assert!(matches!(
+            editor().start(30.0).finish(1.5).to_script(),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+}
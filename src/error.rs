@@ -1,26 +1,303 @@
+use crate::track::TrackKind;
 use std::borrow::Cow;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
+/// Errors returned by every fallible operation of the crate.
 #[derive(Debug)]
 pub enum Error {
+    /// No Track is currently playing.
     NotPlaying,
-    NoData,
-    DeserializationFailed,
-    AppCommandFailed,
+
+    /// The targeted application is not running (AppleEvent error -600).
+    NotRunning(ScriptError),
+
+    /// The user did not allow automation of the targeted application (AppleEvent error -1743).
+    PermissionDenied(ScriptError),
+
+    /// The requested object does not exist (AppleEvent error -1728).
+    ObjectNotFound(ScriptError),
+
+    /// The script failed for any other reason.
+    ScriptFailed(ScriptError),
+
+    /// The script did not complete within the allowed duration.
+    Timeout(Duration),
+
+    /// The script was aborted through a `CancellationToken`.
+    Cancelled,
+
+    /// The output of a script could not be deserialized.
+    Deserialization {
+        /// The JSON path of the failing field, e.g. `tracks[3].rating`
+        path: String,
+
+        /// The underlying serde error
+        source: serde_json::Error,
+    },
+
+    /// A request could not be serialized before being sent to the script.
+    Serialization(serde_json::Error),
+
+    /// A request to the iTunes Store failed.
+    Network(reqwest::Error),
+
+    /// An argument provided to a method is invalid.
+    InvalidArgument(String),
+
+    /// The operation only applies to file Tracks, and was run on another kind of Track.
+    NotFileTrack(TrackKind),
+
+    /// The data of an Artwork is not valid base64.
+    ArtworkDecoding(base64::DecodeError),
+
+    /// The scripting process could not be run.
+    Io(std::io::Error),
 }
 
 impl Display for Error {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let msg = match self {
-            Error::NotPlaying => Cow::Borrowed("Failed to convert values"),
-            Error::NoData => Cow::Borrowed("Failed to get current_track Data!"),
-            Error::DeserializationFailed => {
-                Cow::Borrowed("Failed to deserialize current_track Data!")
+            Error::NotPlaying => Cow::Borrowed("No Track is currently playing"),
+            Error::NotRunning(_) => Cow::Borrowed(
+                "Apple Music is not running, start it with `AppleMusic::run()` and retry",
+            ),
+            Error::PermissionDenied(_) => Cow::Borrowed(
+                "Not allowed to control Apple Music, grant access in System Settings > Privacy & Security > Automation",
+            ),
+            Error::ObjectNotFound(err) => {
+                Cow::Owned(format!("Object not found in Apple Music: {}", err.message))
+            }
+            Error::ScriptFailed(err) => Cow::Owned(err.to_string()),
+            Error::Timeout(duration) => {
+                Cow::Owned(format!("Script execution timed out after {:?}", duration))
             }
-            Error::AppCommandFailed => Cow::Borrowed("Failed to execute AppCommand"),
+            Error::Cancelled => Cow::Borrowed("Script execution was cancelled"),
+            Error::Deserialization { path, source } => Cow::Owned(format!(
+                "Failed to deserialize data at `{}`: {}",
+                path, source
+            )),
+            Error::Serialization(err) => {
+                Cow::Owned(format!("Failed to serialize request: {}", err))
+            }
+            Error::Network(err) => {
+                Cow::Owned(format!("Failed to reach the iTunes Store: {}", err))
+            }
+            Error::InvalidArgument(msg) => Cow::Owned(format!("Invalid argument: {}", msg)),
+            Error::NotFileTrack(kind) => Cow::Owned(format!(
+                "Only file tracks support this operation, got a {:?}",
+                kind
+            )),
+            Error::ArtworkDecoding(err) => {
+                Cow::Owned(format!("Failed to decode artwork data: {}", err))
+            }
+            Error::Io(err) => Cow::Owned(format!("Failed to run script: {}", err)),
         };
 
         f.write_str(&msg)
     }
 }
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::NotRunning(err)
+            | Error::PermissionDenied(err)
+            | Error::ObjectNotFound(err)
+            | Error::ScriptFailed(err) => Some(err),
+            Error::Deserialization { source, .. } => Some(source),
+            Error::Serialization(err) => Some(err),
+            Error::Network(err) => Some(err),
+            Error::ArtworkDecoding(err) => Some(err),
+            Error::Io(err) => Some(err),
+            Error::NotPlaying
+            | Error::Timeout(_)
+            | Error::Cancelled
+            | Error::InvalidArgument(_)
+            | Error::NotFileTrack(_) => None,
+        }
+    }
+}
+
+impl From<ScriptError> for Error {
+    fn from(err: ScriptError) -> Self {
+        match err.code {
+            Some(ScriptError::APP_NOT_RUNNING) => Error::NotRunning(err),
+            Some(ScriptError::AUTOMATION_NOT_PERMITTED) => Error::PermissionDenied(err),
+            Some(ScriptError::OBJECT_NOT_FOUND | ScriptError::INVALID_INDEX) => {
+                Error::ObjectNotFound(err)
+            }
+            _ => Error::ScriptFailed(err),
+        }
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(err: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        Error::Deserialization {
+            path: err.path().to_string(),
+            source: err.into_inner(),
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(err: reqwest::Error) -> Self {
+        Error::Network(err)
+    }
+}
+
+impl From<base64::DecodeError> for Error {
+    fn from(err: base64::DecodeError) -> Self {
+        Error::ArtworkDecoding(err)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(err: std::io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// Failure reported by `osascript` while running a script.
+#[derive(Debug, Clone)]
+pub struct ScriptError {
+    /// The AppleEvent error number, if any (e.g. -1728 when an object is not found)
+    pub code: Option<i32>,
+
+    /// The error message, stripped from `osascript` decorations
+    pub message: String,
+
+    /// The exit code of the `osascript` process, if it exited normally
+    pub exit_code: Option<i32>,
+
+    /// The script that failed
+    pub script: String,
+
+    /// The parameters provided to the script, if any
+    pub params: Option<String>,
+}
+
+impl ScriptError {
+    /// AppleEvent error number raised when the targeted application is not running.
+    pub const APP_NOT_RUNNING: i32 = -600;
+
+    /// AppleEvent error number raised when the requested object does not exist.
+    pub const OBJECT_NOT_FOUND: i32 = -1728;
+
+    /// AppleEvent error number raised when an element is accessed at an index which does not exist,
+    /// e.g. when no Track has the requested persistent ID.
+    pub const INVALID_INDEX: i32 = -1719;
+
+    /// AppleEvent error number raised when the user did not allow automation of the application.
+    pub const AUTOMATION_NOT_PERMITTED: i32 = -1743;
+
+    /// Builds a ScriptError from the stderr output of `osascript`, e.g.
+    /// `execution error: Error: Error: Can't get object. (-1728)`.
+    pub fn from_stderr(
+        stderr: &str,
+        exit_code: Option<i32>,
+        script: &str,
+        params: Option<&str>,
+    ) -> Self {
+        let mut message = stderr.trim();
+
+        if let Some(index) = message.find("execution error:") {
+            message = message[index + "execution error:".len()..].trim_start();
+        }
+
+        while let Some(stripped) = message.strip_prefix("Error:") {
+            message = stripped.trim_start();
+        }
+
+        let mut code = None;
+        if let Some(stripped) = message.strip_suffix(')') {
+            if let Some(index) = stripped.rfind('(') {
+                if let Ok(value) = stripped[index + 1..].parse::<i32>() {
+                    code = Some(value);
+                    message = stripped[..index].trim_end();
+                }
+            }
+        }
+
+        ScriptError {
+            code,
+            message: message.into(),
+            exit_code,
+            script: script.into(),
+            params: params.map(String::from),
+        }
+    }
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self.code {
+            Some(code) => write!(f, "Script failed with error {}: {}", code, self.message),
+            None => write!(f, "Script failed: {}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(stderr: &str) -> ScriptError {
+        ScriptError::from_stderr(stderr, Some(1), "script", None)
+    }
+
+    #[test]
+    fn parses_app_not_running() {
+        let err = parse("execution error: Error: Error: Application isn't running. (-600)\n");
+
+        assert_eq!(err.code, Some(ScriptError::APP_NOT_RUNNING));
+        assert_eq!(err.message, "Application isn't running.");
+        assert!(matches!(Error::from(err), Error::NotRunning(_)));
+    }
+
+    #[test]
+    fn parses_object_not_found() {
+        let err = parse("execution error: Error: Error: Can't get object. (-1728)\n");
+
+        assert_eq!(err.code, Some(ScriptError::OBJECT_NOT_FOUND));
+        assert_eq!(err.message, "Can't get object.");
+        assert!(matches!(Error::from(err), Error::ObjectNotFound(_)));
+    }
+
+    #[test]
+    fn parses_invalid_index() {
+        let err = parse("execution error: Error: Error: Invalid index. (-1719)\n");
+
+        assert_eq!(err.code, Some(ScriptError::INVALID_INDEX));
+        assert_eq!(err.message, "Invalid index.");
+        assert!(matches!(Error::from(err), Error::ObjectNotFound(_)));
+    }
+
+    #[test]
+    fn parses_automation_not_permitted() {
+        let err = parse(
+            "execution error: Error: Error: Not authorized to send Apple events to Music. (-1743)\n",
+        );
+
+        assert_eq!(err.code, Some(ScriptError::AUTOMATION_NOT_PERMITTED));
+        assert_eq!(err.message, "Not authorized to send Apple events to Music.");
+        assert!(matches!(Error::from(err), Error::PermissionDenied(_)));
+    }
+
+    #[test]
+    fn keeps_unparseable_output() {
+        let err = parse("osascript: couldn't load scripting component (JavaScript)\n");
+
+        assert_eq!(err.code, None);
+        assert_eq!(
+            err.message,
+            "osascript: couldn't load scripting component (JavaScript)"
+        );
+        assert_eq!(err.exit_code, Some(1));
+        assert!(matches!(Error::from(err), Error::ScriptFailed(_)));
+    }
+}
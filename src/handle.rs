@@ -0,0 +1,290 @@
+use crate::artwork::ArtworkImage;
+#[cfg(feature = "async")]
+use crate::async_client::AsyncAppleMusicClient;
+use crate::client::AppleMusicClient;
+use crate::editor::TrackEditor;
+use crate::error::Error;
+use crate::id::{PlaylistId, TrackId};
+use crate::paging::TrackPages;
+use crate::playlist::Playlist;
+use crate::track::{Artwork, Track};
+
+/// Lightweight handle on a Track, only holding its id or persistent ID.
+///
+/// Every command is run without fetching the Track first, e.g. from stored persistent IDs:
+/// ```rust,ignore
+/// for persistent_id in stored_ids {
+///     client.track(TrackId::persistent(&persistent_id)).set_favorited(true)?;
+/// }
+/// ```
+#[derive(Clone)]
+pub struct TrackRef<C = AppleMusicClient> {
+    client: C,
+    id: TrackId,
+}
+
+impl<C> TrackRef<C> {
+    pub(crate) fn new(client: C, id: TrackId) -> Self {
+        TrackRef { client, id }
+    }
+
+    /// Returns the id, or persistent ID, of the Track.
+    pub fn id(&self) -> &TrackId {
+        &self.id
+    }
+}
+
+impl<C> From<&TrackRef<C>> for TrackId {
+    fn from(track: &TrackRef<C>) -> Self {
+        track.id.clone()
+    }
+}
+
+impl TrackRef<AppleMusicClient> {
+    /// Returns a new TrackEditor, collecting changes to the Track to apply them in a single script.
+    pub fn edit(&self) -> TrackEditor {
+        self.client.edit_track(self)
+    }
+
+    /// Fetches and returns the full Track.
+    pub fn fetch(&self) -> Result<Track, Error> {
+        self.client.get_track(self)
+    }
+
+    /// Returns true if the Track still exists in Apple Music.
+    pub fn exists(&self) -> Result<bool, Error> {
+        self.client.track_exists(self)
+    }
+
+    /// Returns a list of all artworks of the Track, with their raw_data.
+    pub fn fetch_artworks(&self) -> Result<Vec<Artwork>, Error> {
+        self.client.fetch_track_artworks(self)
+    }
+
+    /// Adds the provided image as the last artwork of the Track.
+    pub fn add_artwork(&self, image: &ArtworkImage) -> Result<(), Error> {
+        self.client.add_track_artwork(self, image)
+    }
+
+    /// Replaces the first artwork of the Track with the provided image, or adds it if there is none.
+    pub fn replace_artwork(&self, image: &ArtworkImage) -> Result<(), Error> {
+        self.client.replace_track_artwork(self, image)
+    }
+
+    /// Deletes every artwork of the Track.
+    pub fn remove_artworks(&self) -> Result<(), Error> {
+        self.client.remove_track_artworks(self)
+    }
+
+    /// Updates the Track from the metadata of its file. Returns an `Error::NotFileTrack` if it is not a file Track.
+    pub fn refresh(&self) -> Result<(), Error> {
+        self.client.refresh_track(self)
+    }
+
+    /// Plays the Track on AppleMusic player.
+    pub fn play(&self) -> Result<(), Error> {
+        self.client.play_track(self)
+    }
+
+    /// Tries to convert the Track.
+    pub fn convert(&self) -> Result<(), Error> {
+        self.client.convert_track(self)
+    }
+
+    /// Reveals and selects the Track in Apple Music.
+    pub fn reveal_in_player(&self) -> Result<(), Error> {
+        self.client.reveal_track(self)
+    }
+
+    /// Triggers a download on Apple Music Player for the Track.
+    pub fn download(&self) -> Result<(), Error> {
+        self.client.download_track(self)
+    }
+
+    /// Favorites / "Unfavorites" the Track.
+    pub fn set_favorited(&self, value: bool) -> Result<(), Error> {
+        self.client.set_track_favorited(self, value)
+    }
+
+    /// Dislikes / "Undislikes" the Track.
+    pub fn set_disliked(&self, value: bool) -> Result<(), Error> {
+        self.client.set_track_disliked(self, value)
+    }
+}
+
+#[cfg(feature = "async")]
+impl TrackRef<AsyncAppleMusicClient> {
+    /// Returns a new TrackEditor, collecting changes to the Track to apply them in a single script.
+    pub fn edit(&self) -> TrackEditor<AsyncAppleMusicClient> {
+        self.client.edit_track(self)
+    }
+
+    /// Fetches and returns the full Track.
+    pub async fn fetch(&self) -> Result<Track, Error> {
+        self.client.get_track(self).await
+    }
+
+    /// Returns true if the Track still exists in Apple Music.
+    pub async fn exists(&self) -> Result<bool, Error> {
+        self.client.track_exists(self).await
+    }
+
+    /// Returns a list of all artworks of the Track, with their raw_data.
+    pub async fn fetch_artworks(&self) -> Result<Vec<Artwork>, Error> {
+        self.client.fetch_track_artworks(self).await
+    }
+
+    /// Adds the provided image as the last artwork of the Track.
+    pub async fn add_artwork(&self, image: &ArtworkImage) -> Result<(), Error> {
+        self.client.add_track_artwork(self, image).await
+    }
+
+    /// Replaces the first artwork of the Track with the provided image, or adds it if there is none.
+    pub async fn replace_artwork(&self, image: &ArtworkImage) -> Result<(), Error> {
+        self.client.replace_track_artwork(self, image).await
+    }
+
+    /// Deletes every artwork of the Track.
+    pub async fn remove_artworks(&self) -> Result<(), Error> {
+        self.client.remove_track_artworks(self).await
+    }
+
+    /// Updates the Track from the metadata of its file. Returns an `Error::NotFileTrack` if it is not a file Track.
+    pub async fn refresh(&self) -> Result<(), Error> {
+        self.client.refresh_track(self).await
+    }
+
+    /// Plays the Track on AppleMusic player.
+    pub async fn play(&self) -> Result<(), Error> {
+        self.client.play_track(self).await
+    }
+
+    /// Tries to convert the Track.
+    pub async fn convert(&self) -> Result<(), Error> {
+        self.client.convert_track(self).await
+    }
+
+    /// Reveals and selects the Track in Apple Music.
+    pub async fn reveal_in_player(&self) -> Result<(), Error> {
+        self.client.reveal_track(self).await
+    }
+
+    /// Triggers a download on Apple Music Player for the Track.
+    pub async fn download(&self) -> Result<(), Error> {
+        self.client.download_track(self).await
+    }
+
+    /// Favorites / "Unfavorites" the Track.
+    pub async fn set_favorited(&self, value: bool) -> Result<(), Error> {
+        self.client.set_track_favorited(self, value).await
+    }
+
+    /// Dislikes / "Undislikes" the Track.
+    pub async fn set_disliked(&self, value: bool) -> Result<(), Error> {
+        self.client.set_track_disliked(self, value).await
+    }
+}
+
+/// Lightweight handle on a Playlist, only holding its id or persistent ID.
+#[derive(Clone)]
+pub struct PlaylistRef<C = AppleMusicClient> {
+    client: C,
+    id: PlaylistId,
+}
+
+impl<C> PlaylistRef<C> {
+    pub(crate) fn new(client: C, id: PlaylistId) -> Self {
+        PlaylistRef { client, id }
+    }
+
+    /// Returns the id, or persistent ID, of the Playlist.
+    pub fn id(&self) -> &PlaylistId {
+        &self.id
+    }
+}
+
+impl<C> From<&PlaylistRef<C>> for PlaylistId {
+    fn from(playlist: &PlaylistRef<C>) -> Self {
+        playlist.id.clone()
+    }
+}
+
+impl PlaylistRef<AppleMusicClient> {
+    /// Fetches and returns the full Playlist, without its Tracks.
+    pub fn fetch(&self) -> Result<Playlist, Error> {
+        self.client.get_playlist(self)
+    }
+
+    /// Returns true if the Playlist still exists in Apple Music.
+    pub fn exists(&self) -> Result<bool, Error> {
+        self.client.playlist_exists(self)
+    }
+
+    /// Fetches and returns the Tracks of the Playlist.
+    pub fn fetch_tracks(&self) -> Result<Vec<Track>, Error> {
+        self.client.fetch_playlist_tracks(self)
+    }
+
+    /// Returns an iterator fetching the Tracks of the Playlist page by page, `page_size` Tracks per script.
+    pub fn track_pages(&self, page_size: usize) -> TrackPages {
+        self.client.playlist_track_pages(self, page_size)
+    }
+
+    /// Searches inside the Playlist for Tracks containing provided query and returns them.
+    pub fn search_for_tracks(&self, query: &str) -> Result<Vec<Track>, Error> {
+        self.client.search_in_playlist(self, query)
+    }
+
+    /// Plays the Playlist on AppleMusic player.
+    pub fn play(&self) -> Result<(), Error> {
+        self.client.play_playlist(self)
+    }
+
+    /// Reveals and selects the Playlist in Apple Music player.
+    pub fn reveal_in_player(&self) -> Result<(), Error> {
+        self.client.reveal_playlist(self)
+    }
+
+    /// Triggers a download on Apple Music Player for the Playlist.
+    pub fn download(&self) -> Result<(), Error> {
+        self.client.download_playlist(self)
+    }
+}
+
+#[cfg(feature = "async")]
+impl PlaylistRef<AsyncAppleMusicClient> {
+    /// Fetches and returns the full Playlist, without its Tracks.
+    pub async fn fetch(&self) -> Result<Playlist, Error> {
+        self.client.get_playlist(self).await
+    }
+
+    /// Returns true if the Playlist still exists in Apple Music.
+    pub async fn exists(&self) -> Result<bool, Error> {
+        self.client.playlist_exists(self).await
+    }
+
+    /// Fetches and returns the Tracks of the Playlist.
+    pub async fn fetch_tracks(&self) -> Result<Vec<Track>, Error> {
+        self.client.fetch_playlist_tracks(self).await
+    }
+
+    /// Searches inside the Playlist for Tracks containing provided query and returns them.
+    pub async fn search_for_tracks(&self, query: &str) -> Result<Vec<Track>, Error> {
+        self.client.search_in_playlist(self, query).await
+    }
+
+    /// Plays the Playlist on AppleMusic player.
+    pub async fn play(&self) -> Result<(), Error> {
+        self.client.play_playlist(self).await
+    }
+
+    /// Reveals and selects the Playlist in Apple Music player.
+    pub async fn reveal_in_player(&self) -> Result<(), Error> {
+        self.client.reveal_playlist(self).await
+    }
+
+    /// Triggers a download on Apple Music Player for the Playlist.
+    pub async fn download(&self) -> Result<(), Error> {
+        self.client.download_playlist(self).await
+    }
+}
@@ -0,0 +1,94 @@
+use crate::jxa::Jxa;
+use crate::playlist::Playlist;
+use crate::track::Track;
+use serde_json::json;
+
+/// Reference to a Track, either by session id or by persistent ID.
+///
+/// The session `id` is only valid until Apple Music is restarted, while the persistent ID never changes:
+/// references meant to be stored should use `TrackId::persistent`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TrackId {
+    /// The id of the Track, as in `Track.id`
+    Id(i32),
+
+    /// The persistent ID of the Track, as in `Track.persistent_id`
+    PersistentId(String),
+}
+
+impl TrackId {
+    /// Creates a reference to the Track with the provided persistent ID.
+    pub fn persistent(persistent_id: &str) -> Self {
+        TrackId::PersistentId(persistent_id.into())
+    }
+
+    /// Returns the specifier of the Track, e.g. `Application("Music").tracks.byId(42)`.
+    pub(crate) fn specifier(&self, app: &Jxa) -> Jxa {
+        match self {
+            TrackId::Id(id) => app.property("tracks").by_id(*id),
+            TrackId::PersistentId(persistent_id) => {
+                by_persistent_id(app.property("tracks"), persistent_id)
+            }
+        }
+    }
+}
+
+impl From<i32> for TrackId {
+    fn from(id: i32) -> Self {
+        TrackId::Id(id)
+    }
+}
+
+impl From<&Track> for TrackId {
+    fn from(track: &Track) -> Self {
+        TrackId::Id(track.id)
+    }
+}
+
+/// Reference to a Playlist, either by session id or by persistent ID.
+///
+/// The session `id` is only valid until Apple Music is restarted, while the persistent ID never changes:
+/// references meant to be stored should use `PlaylistId::persistent`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PlaylistId {
+    /// The id of the Playlist, as in `Playlist.id`
+    Id(i32),
+
+    /// The persistent ID of the Playlist, as in `Playlist.persistent_id`
+    PersistentId(String),
+}
+
+impl PlaylistId {
+    /// Creates a reference to the Playlist with the provided persistent ID.
+    pub fn persistent(persistent_id: &str) -> Self {
+        PlaylistId::PersistentId(persistent_id.into())
+    }
+
+    /// Returns the specifier of the Playlist, e.g. `Application("Music").playlists.byId(42)`.
+    pub(crate) fn specifier(&self, app: &Jxa) -> Jxa {
+        match self {
+            PlaylistId::Id(id) => app.property("playlists").by_id(*id),
+            PlaylistId::PersistentId(persistent_id) => {
+                by_persistent_id(app.property("playlists"), persistent_id)
+            }
+        }
+    }
+}
+
+impl From<i32> for PlaylistId {
+    fn from(id: i32) -> Self {
+        PlaylistId::Id(id)
+    }
+}
+
+impl From<&Playlist> for PlaylistId {
+    fn from(playlist: &Playlist) -> Self {
+        PlaylistId::Id(playlist.id)
+    }
+}
+
+/// Returns the element of the collection with the provided persistent ID, e.g. `tracks.whose({...})[0]`.
+fn by_persistent_id(collection: Jxa, persistent_id: &str) -> Jxa {
+    let filter = json!({ "persistentID": { "_equals": persistent_id } });
+    collection.call("whose", &[Jxa::json(&filter)]).at(0)
+}
@@ -0,0 +1,128 @@
+use crate::error::Error;
+use std::path::Path;
+
+/// Extensions of the files Apple Music can add to its library, in lowercase.
+pub const SUPPORTED_EXTENSIONS: [&str; 14] = [
+    "aac", "aif", "aifc", "aiff", "caf", "m4a", "m4b", "m4p", "m4r", "m4v", "mov", "mp3", "mp4",
+    "wav",
+];
+
+/// Returns the absolute form of every provided path, or an `Error::InvalidArgument` if any path
+/// does not exist or is a file of an unsupported format. Folders are added as a whole by Apple Music.
+pub(crate) fn validate_paths<P: AsRef<Path>>(paths: &[P]) -> Result<Vec<String>, Error> {
+    paths
+        .iter()
+        .map(|path| validate_path(path.as_ref()))
+        .collect()
+}
+
+fn validate_path(path: &Path) -> Result<String, Error> {
+    let absolute = path.canonicalize().map_err(|err| {
+        Error::InvalidArgument(format!("cannot add `{}`: {}", path.display(), err))
+    })?;
+
+    if absolute.is_file() {
+        let extension = absolute
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase)
+            .unwrap_or_default();
+
+        if !SUPPORTED_EXTENSIONS.contains(&extension.as_str()) {
+            return Err(Error::InvalidArgument(format!(
+                "cannot add `{}`: unsupported format, expected one of {}",
+                path.display(),
+                SUPPORTED_EXTENSIONS.join(", ")
+            )));
+        }
+    }
+
+    absolute.into_os_string().into_string().map_err(|_| {
+        Error::InvalidArgument(format!(
+            "cannot add `{}`: path is not valid UTF-8",
+            path.display()
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+    use std::path::PathBuf;
+
+    /// Folder in the temporary directory, removed once dropped.
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path = env::temp_dir().join(format!(
+                "apple-music-import-{}-{}",
+                std::process::id(),
+                name
+            ));
+            fs::create_dir_all(&path).unwrap();
+            TempDir(path)
+        }
+
+        fn file(&self, name: &str) -> PathBuf {
+            let path = self.0.join(name);
+            fs::write(&path, b"").unwrap();
+            path
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn canonical(path: &Path) -> String {
+        path.canonicalize().unwrap().to_str().unwrap().into()
+    }
+
+    #[test]
+    fn accepts_supported_files_and_folders() {
+        let dir = TempDir::new("supported");
+        let song = dir.file("song.mp3");
+        let video = dir.file("Video.M4V");
+
+        assert_eq!(
+            validate_paths(&[&song, &video, &dir.0]).unwrap(),
+            [canonical(&song), canonical(&video), canonical(&dir.0)]
+        );
+    }
+
+    #[test]
+    fn rejects_missing_paths() {
+        let dir = TempDir::new("missing");
+
+        assert!(matches!(
+            validate_paths(&[dir.0.join("missing.mp3")]),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        let dir = TempDir::new("unsupported");
+        let song = dir.file("song.mp3");
+
+        for name in ["cover.png", "notes", "song.mp3.txt"] {
+            assert!(matches!(
+                validate_paths(&[&song, &dir.file(name)]),
+                Err(Error::InvalidArgument(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn makes_relative_paths_absolute() {
+        let paths = validate_paths(&["src/../src"]).unwrap();
+
+        assert_eq!(paths, [canonical(&env::current_dir().unwrap().join("src"))]);
+        assert!(Path::new(&paths[0]).is_absolute());
+    }
+}
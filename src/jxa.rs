@@ -0,0 +1,240 @@
+use crate::error::Error;
+use serde::Serialize;
+use serde_json::Value;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+
+/// JavaScript for Automation (JXA) expression, such as an object specifier or a command.
+///
+/// Every argument is JSON-encoded, and every property or method name which is not a plain
+/// identifier is accessed through a JSON-encoded key: no value can escape its literal.
+/// ```rust
+/// use apple_music::Jxa;
+///
+/// let name = "\"); Application('Finder').delete(";
+/// let cmd = Jxa::application("Music").property("tracks").by_id(42).property("name").set(name);
+///
+/// assert_eq!(
+///     cmd.to_string(),
+///     r#"Application("Music").tracks.byId(42).name = "\"); Application('Finder').delete(""#
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jxa(String);
+
+impl Jxa {
+    /// Returns the specifier of the provided application, e.g. `Application("Music")`.
+    pub fn application(name: &str) -> Self {
+        Jxa(format!("Application({})", Jxa::json(&Value::from(name))))
+    }
+
+    /// Returns the JSON literal of the provided value, e.g. `"name"`, `42` or `[1,2]`,
+    /// or an `Error::Serialization` if it cannot be represented in JSON, e.g. a map with non-string keys.
+    pub fn value<T: Serialize + ?Sized>(value: &T) -> Result<Self, Error> {
+        let json = serde_json::to_string(value).map_err(Error::Serialization)?;
+        Ok(Jxa::literal(json))
+    }
+
+    /// Returns the JSON literal of the provided JSON value, which cannot fail.
+    pub fn json(value: &Value) -> Self {
+        Jxa::literal(value.to_string())
+    }
+
+    /// Wraps a raw JavaScript expression. The caller is responsible for its safety.
+    pub fn raw(expression: &str) -> Self {
+        Jxa(expression.into())
+    }
+
+    /// Accesses a property or an element collection, e.g. `.tracks` or `.name`.
+    pub fn property(&self, name: &str) -> Self {
+        Jxa(format!("{}{}", self.0, Jxa::member(name)))
+    }
+
+    /// Accesses an element by id, e.g. `.byId(42)`.
+    pub fn by_id<T: Into<Value>>(&self, id: T) -> Self {
+        self.call("byId", &[Jxa::json(&id.into())])
+    }
+
+    /// Accesses an element by index, e.g. `[0]`.
+    pub fn at(&self, index: usize) -> Self {
+        Jxa(format!("{}[{}]", self.0, index))
+    }
+
+    /// Calls a method with the provided arguments, e.g. `.play(Application("Music").tracks.byId(42))`.
+    pub fn call(&self, method: &str, args: &[Jxa]) -> Self {
+        let args = args
+            .iter()
+            .map(|arg| arg.0.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        Jxa(format!("{}{}({})", self.0, Jxa::member(method), args))
+    }
+
+    /// Assigns the provided value to this specifier, e.g. `.favorited = true`.
+    pub fn set<T: Into<Value>>(&self, value: T) -> Self {
+        self.assign(&Jxa::json(&value.into()))
+    }
+
+    /// Assigns the provided expression to this specifier.
+    pub fn assign(&self, expression: &Jxa) -> Self {
+        Jxa(format!("{} = {}", self.0, expression.0))
+    }
+
+    /// Returns the expression as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    fn literal(json: String) -> Self {
+        // Line & paragraph separators are valid in JSON strings, but not in older JavaScript literals.
+        Jxa(json
+            .replace('\u{2028}', "\\u2028")
+            .replace('\u{2029}', "\\u2029"))
+    }
+
+    fn member(name: &str) -> String {
+        let mut chars = name.chars();
+        let is_identifier = chars
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_' || c == '$')
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '$');
+
+        if is_identifier {
+            format!(".{}", name)
+        } else {
+            format!("[{}]", Jxa::json(&Value::from(name)))
+        }
+    }
+}
+
+impl Display for Jxa {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Jxa> for String {
+    fn from(jxa: Jxa) -> Self {
+        jxa.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::quickcheck;
+
+    /// Fragments able to end a string literal, a line or a comment if not escaped.
+    const FRAGMENTS: [&str; 12] = [
+        "\"",
+        "\\",
+        "'",
+        "`",
+        "${",
+        "*/",
+        "\n",
+        "\r",
+        "\u{2028}",
+        "\u{2029}",
+        "\0",
+        "</script>",
+    ];
+
+    /// Interleaves random text with fragments picked by `picks`.
+    fn tricky(text: &str, picks: &[u8]) -> String {
+        let mut chars = text.chars();
+        let mut value = String::new();
+
+        for pick in picks {
+            value.push_str(FRAGMENTS[usize::from(*pick) % FRAGMENTS.len()]);
+            value.extend(chars.next());
+        }
+
+        value.extend(chars);
+        value
+    }
+
+    /// Asserts that `literal` is a single JavaScript string literal holding `expected`.
+    fn assert_string_literal(literal: &str, expected: &str) {
+        assert!(literal.starts_with('"'), "{:?}", literal);
+
+        let mut chars = literal.char_indices().skip(1);
+        let mut end = None;
+
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '"' => {
+                    end = Some(index);
+                    break;
+                }
+                '\n' | '\r' | '\u{2028}' | '\u{2029}' => {
+                    panic!("line terminator in {:?}", literal)
+                }
+                _ => {}
+            }
+        }
+
+        assert_eq!(end, Some(literal.len() - 1), "{:?}", literal);
+        assert_eq!(
+            serde_json::from_str::<String>(literal).unwrap(),
+            expected,
+            "{:?}",
+            literal
+        );
+    }
+
+    #[test]
+    fn escapes_special_characters() {
+        for fragment in FRAGMENTS {
+            let value = format!("a{}b", fragment);
+            assert_string_literal(Jxa::value(&value).unwrap().as_str(), &value);
+        }
+
+        assert_eq!(
+            Jxa::value("\u{2028}\u{2029}").unwrap().as_str(),
+            r#""\u2028\u2029""#
+        );
+        assert_eq!(
+            Jxa::json(&Value::from("\u{2028}\u{2029}")).as_str(),
+            r#""\u2028\u2029""#
+        );
+    }
+
+    #[test]
+    fn rejects_values_which_are_not_json() {
+        let value = std::collections::HashMap::from([((1, 2), 3)]);
+        assert!(matches!(Jxa::value(&value), Err(Error::Serialization(_))));
+    }
+
+    quickcheck! {
+        fn value_is_a_single_literal(text: String, picks: Vec<u8>) -> bool {
+            let value = tricky(&text, &picks);
+            assert_string_literal(Jxa::value(&value).unwrap().as_str(), &value);
+            true
+        }
+
+        fn set_assigns_a_single_literal(text: String, picks: Vec<u8>) -> bool {
+            let value = tricky(&text, &picks);
+            let statement = Jxa::raw("track").property("name").set(value.as_str());
+
+            let literal = statement.as_str().strip_prefix("track.name = ").unwrap();
+            assert_string_literal(literal, &value);
+            true
+        }
+
+        fn property_keeps_name_in_a_literal(text: String, picks: Vec<u8>) -> bool {
+            let name = tricky(&text, &picks);
+            let property = Jxa::raw("track").property(&name);
+
+            match property.as_str().strip_prefix("track[") {
+                Some(key) => assert_string_literal(key.strip_suffix(']').unwrap(), &name),
+                None => assert_eq!(property.as_str(), format!("track.{}", name)),
+            }
+            true
+        }
+    }
+}
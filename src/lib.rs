@@ -1,109 +1,175 @@
 //! # Apple Music
 //! _A Rust Library to fully control local MacOS Apple Music player._
-//
+//!
 //! [![crates.io](https://img.shields.io/crates/v/apple-music.svg)](https://crates.io/crates/apple-music)
 //! [![maintenance-status](https://img.shields.io/badge/maintenance-actively--developed-brightgreen.svg)](https://crates.io/crates/apple-music)
 //! [![docs.rs](https://img.shields.io/docsrs/apple-music)](https://docs.rs/apple-music/latest)
-//
+//!
 //! This crate provides a convenient way of controlling a MacOS Apple Music player, fully through Rust code.
 //! The logic behind this crate relies on Apple's scripting APIs through [`osascript` CLI](https://ss64.com/mac/osascript.html) and `JavaScript` scripts.
-//
+//!
 //! ## Installation
 //! `apple-music` is available directly on crates.io:
 //! ```shell
 //! cargo add apple-music
 //! ```
-//
+//!
 //! ## How-to
 //! Import the library in your project:
 //! ```rust
-//! use apple-music::AppleMusic;
+//! use apple_music::AppleMusic;
 //! ```
-//
+//!
 //! The library entry point is `AppleMusic`. From there, you can:
 //! - Get the application's data - `AppleMusic::get_application_data();` -> `ApplicationData`
 //! - Get the current track - `AppleMusic::get_current_track();` -> `Track`
 //!   - Track can then be used directly:
 //!     - Favorite / dislike Track - `track.set_favorited(true);` or `track.set_disliked(true);`
 //!     - Download Track - `track.download()`
+//!     - Edit Track properties in a single script - `track.edit().name("One More Time").rating(80).year(2000).apply()`
+//!     - Export its artworks - `track.fetch_artworks_raw_data();`, then `artwork.image()` -> `ArtworkImage` with its `ImageFormat` & dimensions, and `image.write_in("covers", "cover")` -> `PathBuf`
+//!     - Set or remove its artworks - `track.replace_artwork(&ArtworkImage::open("cover.png")?);`, `track.add_artwork(&image);` or `track.remove_artworks();`
+//!     - Refresh a file Track after rewriting its tags on disk - `track.refresh();`, or many at once - `AppleMusic::refresh_tracks(&tracks);` -> `Vec<TrackResult>`
 //!     - Reveal Track in Player - `track.reveal_in_player()`
-//
-//
+//!
+//!
 //! - Get the current playlist - `AppleMusic::get_current_playlist();` -> `Playlist`
 //!   - Playlist can then be used directly:
 //!     - Search for a track in a playlist - `playlist.search_for_tracks(track_name)` -> `Vec<Track>`
 //!     - Reveal Playlist in player - `playlist.reveal_in_player()`
 //!     - Download Playlist - `playlist.download()`
-//
+//!
 //! To control the player, you can do it directly using `AppleMusic`:
 //! - Set the volume - `AppleMusic::set_sound_volume(50);`
 //! - Change track - `AppleMusic::next_track();`
 //! - Play specific Track - `AppleMusic::play_track(Track);`
 //! - Pause - `AppleMusic::pause();`
+//! - Get a Track back after a restart of Apple Music - `AppleMusic::get_track_by_persistent_id(&persistent_id);` -> `Track`
+//! - Control a Track from its stored persistent ID - `AppleMusic::play_track(TrackId::persistent(&persistent_id));`
+//! - Get many stored Tracks back in a single script - `AppleMusic::get_tracks_by_persistent_ids(&persistent_ids);` -> `Vec<Option<Track>>`
+//! - Act on a Track without fetching it first - `AppleMusic::track(TrackId::persistent(&persistent_id)).set_favorited(true);`, then `.fetch()` -> `Track`
+//! - Favorite, dislike or rate a whole Album - `AppleMusic::album("Discovery", "Daft Punk").set_rating(100);`, or `track.get_album().fetch_tracks();` -> `Vec<Track>`
+//! - Add files or folders to the library, or to a Playlist - `AppleMusic::add(&["/Users/me/Music/Import/Discovery"], Some(PlaylistId::persistent(&persistent_id)));` -> `Vec<Track>`
+//! - Find file Tracks whose file is missing from disk - `AppleMusic::find_dead_tracks();` -> `Vec<DeadTrack>`
 //! - Quit the application - `AppleMusic::quit();`
-//
-//
+//! - Count Library Tracks, or check whether a Playlist still exists - `AppleMusic::count_library_tracks();` -> `usize`, `AppleMusic::playlist_exists(id);` -> `bool`
+//! - Search the whole library - `AppleMusic::search("Daft Punk", SearchKind::Artists);` -> `Vec<Track>`
+//! - Fetch Library Tracks page by page, e.g. to report progress - `AppleMusic::library_track_pages(1000);` -> `Iterator<Item = Result<TrackPage, Error>>`
+//! - Fetch only some fields of every Library Track, much faster - `AppleMusic::get_all_library_partial_tracks(&[TrackField::Id, TrackField::Name]);` -> `Vec<PartialTrack>`
+//!
+//! Every `AppleMusic` function is a thin wrapper over a default `AppleMusicClient`.
+//! A client can run its commands through any `ScriptBackend` implementation (mocks, recorders...):
+//! - Configure a client - `AppleMusicClient::builder().app_name("Music").timeout(Duration::from_secs(5)).build();`
+//! - Keep a single `osascript` process alive between calls - `AppleMusicClient::builder().persistent_session(true).build();`
+//! - Override the timeout or cancel a single call - `client.with_timeout(Duration::from_secs(60)).with_cancellation(&token).get_all_library_tracks();`
+//! - Create a client with a custom backend - `AppleMusicClient::with_backend(MyBackend);`
+//! - Use it like `AppleMusic` - `client.get_current_track();` -> `Track`
+//! - Filter Tracks inside Apple Music - `client.tracks().filter(TrackField::Artist, Operator::Equals, "Daft Punk").filter(TrackField::Rating, Operator::GreaterThanOrEquals, 80).execute();` -> `Vec<Track>`
+//! - Edit every matching Track in a single script - `client.tracks().filter(TrackField::Album, Operator::Equals, "Discovery").edit().genre("House").apply();` -> `Vec<TrackResult>`
+//! - Run several commands in a single script - `client.batch().set_shuffle(true).set_sound_volume(40).play_playlist(&playlist).execute();` -> `Vec<Result<(), Error>>`
+//!
+//! With the `async` feature, `AsyncAppleMusicClient` exposes the same API as async methods, relying on `tokio`:
+//! - Get the current track - `AsyncAppleMusicClient::default().get_current_track().await;` -> `Track`
+//!
+//!
 //! That is just a part of the available API, without even mentioning the data you have access to.
-//
+//!
 //! For more info and an exhaustive list of what's available, please check out the [documentation](https://docs.rs/apple-music/latest)!
-//
-//
+//!
+//!
 //! ## Example
-//! ```rust
+//! ```rust,ignore
 //! let playlist = &AppleMusic::get_playlist_by_id(1234).unwrap();
 //! AppleMusic::play_playlist(playlist); //! Apple Music player starts playing provided Playlist.
-//
+//!
 //! AppleMusic::set_shuffle(true); //! Shuffle is now enabled on currently playing Playlist.
-//
+//!
 //! let track = playlist.fetch_playlist_tracks().unwrap()[5];
 //! AppleMusic::play_track(track); //! Apple Music player starts playing provided Track.
-//
+//!
 //! let current_track = AppleMusic::get_current_track().unwrap();
 //! println!("{}", current_track.name()); //! "An awesome song!"
-//
+//!
 //! println!("{}", current_track.artwork_url()); //! Prints the direct url for the Artwork of the Track.
-//
+//!
 //! current_track.set_favorited(true); //! Track is now favorited!
-//
+//!
 //! AppleMusic::next_track(); //! Goes to next track.
-//
+//!
 //! let current_track = AppleMusic::get_current_track().unwrap();
 //! current_track.reveal_in_player(); //! Track is revealed and selected on Apple Music player.
-//
+//!
 //! current_track.set_disliked(true); //! Track is now disliked!
-//
+//!
 //! AppleMusic::set_sound_volume(15); //! Sets Player volume to 15.
-//
+//!
 //! playlist.download(); //! Playlist is being downloaded on Apple Music player.
-//
+//!
 //! AppleMusic::quit(); //! Quit Apple Music application on Mac.
 //! ```
-//
+//!
 //! ## Limitations
 //! ### Platforms
 //! This crate only works on MacOs, and has only been tested with macOS 13.4.1 and Apple Music 1.3.5.
-//
+//!
 //! I would be more than happy provide support for other version of MacOs / Apple Music, do not hesitate to open an issue if you are facing failures!
-//
+//!
 //! ## Next Steps
 //! _Before v1.0:_
 //! - Finish to add remaining classes & methods:
-//!   - `ADD()`
 //!   - `EXPORT()`
-//!   - `REFRESH()`
 //!   - Ensure the whole API is covered by this crate
 
 mod script_controller;
+mod session;
 
 mod error;
+mod id;
 mod track;
 
+mod album;
 mod apple_music;
 mod application_data;
+mod artwork;
+#[cfg(feature = "async")]
+mod async_client;
+mod batch;
+mod calls;
+mod client;
+mod commands;
+mod editor;
+mod handle;
+mod import;
+mod jxa;
+#[cfg(test)]
+mod mock;
+mod paging;
+mod partial_track;
 mod playlist;
+mod query;
+mod search;
+mod selection;
 
+pub use album::*;
 pub use apple_music::*;
 pub use application_data::*;
+pub use artwork::*;
+#[cfg(feature = "async")]
+pub use async_client::*;
+pub use batch::*;
+pub use client::*;
+pub use editor::*;
+pub use error::*;
+pub use handle::*;
+pub use id::*;
+pub use import::*;
+pub use jxa::*;
+pub use paging::*;
+pub use partial_track::*;
 pub use playlist::*;
+pub use query::*;
+pub use script_controller::*;
+pub use search::*;
+pub use selection::*;
+pub use session::*;
 pub use track::*;
-pub use error::*;
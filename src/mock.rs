@@ -0,0 +1,93 @@
+//! `ScriptBackend` used by unit tests to drive the clients without `osascript`.
+use crate::error::Error;
+use crate::script_controller::{ExecutionOptions, ScriptBackend, ScriptRequest};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// Script or request received by a `RecordingBackend`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Sent {
+    /// Raw JXA script
+    Script(String),
+
+    /// JSON of a typed request
+    Request(Value),
+}
+
+/// Backend recording everything it receives, and answering with the queued outputs, in order.
+/// Clones share the same recording, so that a test can keep one after handing the other to a client.
+#[derive(Clone, Default)]
+pub(crate) struct RecordingBackend(Arc<Mutex<Recording>>);
+
+#[derive(Default)]
+struct Recording {
+    sent: Vec<(Sent, Option<Duration>)>,
+    outputs: VecDeque<Result<String, Error>>,
+}
+
+impl RecordingBackend {
+    /// Queues the output of the next script.
+    pub(crate) fn respond(&self, output: &str) -> &Self {
+        self.recording().outputs.push_back(Ok(output.into()));
+        self
+    }
+
+    /// Queues the failure of the next script.
+    pub(crate) fn fail(&self, err: Error) -> &Self {
+        self.recording().outputs.push_back(Err(err));
+        self
+    }
+
+    /// Returns everything received so far.
+    pub(crate) fn sent(&self) -> Vec<Sent> {
+        self.recording()
+            .sent
+            .iter()
+            .map(|(sent, _)| sent.clone())
+            .collect()
+    }
+
+    /// Returns the timeout of everything received so far.
+    pub(crate) fn timeouts(&self) -> Vec<Option<Duration>> {
+        self.recording()
+            .sent
+            .iter()
+            .map(|(_, timeout)| *timeout)
+            .collect()
+    }
+
+    fn record(&self, sent: Sent, options: &ExecutionOptions) -> Result<String, Error> {
+        let mut recording = self.recording();
+        recording.sent.push((sent, options.timeout));
+        recording
+            .outputs
+            .pop_front()
+            .unwrap_or_else(|| Ok(String::new()))
+    }
+
+    fn recording(&self) -> std::sync::MutexGuard<'_, Recording> {
+        self.0.lock().unwrap()
+    }
+}
+
+impl ScriptBackend for RecordingBackend {
+    fn execute(
+        &self,
+        script: &str,
+        _params: Option<&str>,
+        options: &ExecutionOptions,
+    ) -> Result<String, Error> {
+        self.record(Sent::Script(script.into()), options)
+    }
+
+    fn execute_request(
+        &self,
+        request: &ScriptRequest,
+        options: &ExecutionOptions,
+    ) -> Result<String, Error> {
+        let json = serde_json::from_str(&request.to_json()?).unwrap();
+        self.record(Sent::Request(json), options)
+    }
+}
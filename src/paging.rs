@@ -0,0 +1,249 @@
+use crate::client::AppleMusicClient;
+use crate::error::Error;
+use crate::script_controller::ScriptRequest;
+use crate::track::Track;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+/// Number of Tracks fetched per script by `get_all_library_tracks` & `fetch_playlist_tracks`.
+pub const DEFAULT_PAGE_SIZE: usize = 500;
+
+/// Page of Tracks, fetched in a single script.
+/// Holds `PartialTrack`s when fetched with a selection of `TrackField`s.
+#[derive(Deserialize, Debug)]
+pub struct TrackPage<T = Track> {
+    /// Index of the first Track of the page in the whole collection
+    pub offset: usize,
+
+    /// Index following the last Track of the page in the whole collection
+    pub end: usize,
+
+    /// Number of Tracks in the whole collection
+    pub total: usize,
+
+    /// Tracks of the page. Tracks which could not be read are skipped.
+    pub tracks: Vec<T>,
+}
+
+impl<T> TrackPage<T> {
+    /// Returns the fraction of the collection fetched once this page is, from 0.0 to 1.0.
+    pub fn progress(&self) -> f64 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.end as f64 / self.total as f64
+        }
+    }
+}
+
+/// Iterator over the pages of a Track collection, running one script per page.
+///
+/// e.g. reporting progress while fetching the whole library:
+/// ```rust,ignore
+/// let mut tracks = Vec::new();
+///
+/// for page in client.library_track_pages(1000) {
+///     let page = page?;
+///     println!("{} / {}", page.end, page.total);
+///     tracks.extend(page.tracks);
+/// }
+/// ```
+pub struct TrackPages<T = Track> {
+    client: AppleMusicClient,
+    request: ScriptRequest,
+    cursor: PageCursor<T>,
+}
+
+impl<T: DeserializeOwned + 'static> TrackPages<T> {
+    pub(crate) fn new(client: AppleMusicClient, request: ScriptRequest, page_size: usize) -> Self {
+        TrackPages {
+            client,
+            cursor: PageCursor::new(&request, page_size),
+            request,
+        }
+    }
+
+    /// Fetches every remaining page and returns all of their Tracks.
+    pub fn collect_tracks(self) -> Result<Vec<T>, Error> {
+        let mut tracks = Vec::new();
+
+        for page in self {
+            tracks.extend(page?.tracks);
+        }
+
+        Ok(tracks)
+    }
+}
+
+impl<T: DeserializeOwned + 'static> Iterator for TrackPages<T> {
+    type Item = Result<TrackPage<T>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(page) = self.cursor.fetched_page() {
+            return Some(Ok(page));
+        }
+
+        let request = self.cursor.next_request(&self.request)?;
+        let result = self.client.execute_request(&request);
+
+        Some(self.cursor.advance(result))
+    }
+}
+
+/// Position of a paginated fetch, shared by the sync & async page iterators.
+///
+/// Selected fields are read for a whole collection at once by Apple Music, e.g. `tracks.name()`:
+/// a partial fetch runs a single script, whose Tracks are then handed out page by page.
+pub(crate) struct PageCursor<T> {
+    page_size: usize,
+    offset: usize,
+    finished: bool,
+    fetch_all: bool,
+    fetched: Option<(std::vec::IntoIter<T>, usize)>,
+}
+
+impl<T> PageCursor<T> {
+    pub(crate) fn new(request: &ScriptRequest, page_size: usize) -> Self {
+        PageCursor {
+            page_size: page_size.max(1),
+            offset: 0,
+            finished: false,
+            fetch_all: request.track_fields().is_some(),
+            fetched: None,
+        }
+    }
+
+    /// Returns the next page of a collection fetched at once, if any.
+    pub(crate) fn fetched_page(&mut self) -> Option<TrackPage<T>> {
+        let (tracks, total) = self.fetched.take()?;
+        Some(self.split(tracks, total))
+    }
+
+    /// Returns the request of the next page, if any.
+    pub(crate) fn next_request(&self, request: &ScriptRequest) -> Option<ScriptRequest> {
+        if self.finished {
+            return None;
+        }
+
+        match self.fetch_all {
+            true => Some(request.clone()),
+            false => Some(request.clone().page(self.offset, self.page_size)),
+        }
+    }
+
+    /// Moves past the fetched page, and returns it. A failure or the last page ends the fetch.
+    pub(crate) fn advance(
+        &mut self,
+        result: Result<TrackPage<T>, Error>,
+    ) -> Result<TrackPage<T>, Error> {
+        match result {
+            Ok(page) if self.fetch_all => Ok(self.split(page.tracks.into_iter(), page.total)),
+            Ok(page) if page.end > self.offset && page.end < page.total => {
+                self.offset = page.end;
+                Ok(page)
+            }
+            result => {
+                self.finished = true;
+                result
+            }
+        }
+    }
+
+    /// Returns the next page of the Tracks fetched at once, and keeps the remaining ones.
+    fn split(&mut self, mut tracks: std::vec::IntoIter<T>, total: usize) -> TrackPage<T> {
+        let page_tracks: Vec<T> = tracks.by_ref().take(self.page_size).collect();
+        let page = TrackPage {
+            offset: self.offset,
+            end: self.offset + page_tracks.len(),
+            total,
+            tracks: page_tracks,
+        };
+
+        self.offset = page.end;
+        match tracks.len() {
+            0 => self.finished = true,
+            _ => self.fetched = Some((tracks, total)),
+        }
+
+        page
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::partial_track::TrackField;
+    use crate::script_controller::ParamType;
+
+    fn page(offset: usize, end: usize, total: usize) -> TrackPage<usize> {
+        TrackPage {
+            offset,
+            end,
+            total,
+            tracks: (offset..end).collect(),
+        }
+    }
+
+    #[test]
+    fn requests_one_page_per_script() {
+        let request = ScriptRequest::new(ParamType::AllTracks);
+        let mut cursor = PageCursor::new(&request, 2);
+
+        let first = cursor.next_request(&request).unwrap();
+        assert_eq!(first.page_bounds(), Some((0, 2)));
+        assert_eq!(cursor.advance(Ok(page(0, 2, 3))).unwrap().tracks, [0, 1]);
+        assert!(cursor.fetched_page().is_none());
+
+        let second = cursor.next_request(&request).unwrap();
+        assert_eq!(second.page_bounds(), Some((2, 2)));
+        assert_eq!(cursor.advance(Ok(page(2, 3, 3))).unwrap().tracks, [2]);
+
+        assert!(cursor.next_request(&request).is_none());
+    }
+
+    #[test]
+    fn splits_partial_fetch_into_pages() {
+        let request = ScriptRequest::new(ParamType::AllTracks).fields(&[TrackField::Name]);
+        let mut cursor = PageCursor::new(&request, 2);
+
+        let fetch = cursor.next_request(&request).unwrap();
+        assert_eq!(fetch.page_bounds(), None);
+
+        let first = cursor.advance(Ok(page(0, 5, 5))).unwrap();
+        assert_eq!((first.offset, first.end, first.total), (0, 2, 5));
+        assert_eq!(first.tracks, [0, 1]);
+
+        let second = cursor.fetched_page().unwrap();
+        assert_eq!((second.offset, second.end), (2, 4));
+        assert_eq!(second.tracks, [2, 3]);
+
+        let last = cursor.fetched_page().unwrap();
+        assert_eq!((last.offset, last.end), (4, 5));
+        assert_eq!(last.progress(), 1.0);
+
+        assert!(cursor.fetched_page().is_none());
+        assert!(cursor.next_request(&request).is_none());
+    }
+
+    #[test]
+    fn returns_single_page_of_empty_partial_fetch() {
+        let request = ScriptRequest::new(ParamType::AllTracks).fields(&[TrackField::Name]);
+        let mut cursor = PageCursor::new(&request, 2);
+
+        cursor.next_request(&request).unwrap();
+        assert!(cursor.advance(Ok(page(0, 0, 0))).unwrap().tracks.is_empty());
+
+        assert!(cursor.fetched_page().is_none());
+        assert!(cursor.next_request(&request).is_none());
+    }
+
+    #[test]
+    fn stops_after_failure() {
+        let request = ScriptRequest::new(ParamType::AllTracks).fields(&[TrackField::Name]);
+        let mut cursor = PageCursor::<usize>::new(&request, 2);
+
+        cursor.next_request(&request).unwrap();
+        assert!(cursor.advance(Err(Error::Cancelled)).is_err());
+        assert!(cursor.next_request(&request).is_none());
+    }
+}
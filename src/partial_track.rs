@@ -0,0 +1,304 @@
+use crate::track::{CloudStatus, Kind, MediaKind, TrackKind};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// Declares both `TrackField` and `PartialTrack` from a single list of documented fields:
+/// `Variant as "jxaName" => field: Type`, where the JXA name defaults to the camelCase variant.
+macro_rules! track_fields {
+    ($(
+        $(#[doc = $doc:literal])*
+        $variant:ident $(as $name:literal)? => $field:ident: $type:ty,
+    )*) => {
+        /// Track property which can be selected when fetching `PartialTrack`s.
+        ///
+        /// Each selected field is fetched for the whole collection at once (e.g. `tracks.name()`),
+        /// which is much faster than fetching every property of every Track.
+        #[derive(Serialize, Display, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[serde(rename_all = "camelCase")]
+        #[strum(serialize_all = "camelCase")]
+        pub enum TrackField {
+            $(
+                $(#[doc = $doc])*
+                $(#[serde(rename = $name)] #[strum(serialize = $name)])?
+                $variant,
+            )*
+        }
+
+        /// Lightweight Track, only holding the `TrackField`s it was fetched with.
+        ///
+        /// Every field which was not selected is `None`.
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        pub struct PartialTrack {
+            $(
+                $(#[doc = $doc])*
+                $(#[serde(rename = $name)])?
+                pub $field: Option<$type>,
+            )*
+        }
+    };
+}
+
+track_fields! {
+    /// The class of the Track
+    Class => class: TrackKind,
+
+    /// The id of the item
+    Id => id: i32,
+
+    /// The index of the item in internal application order
+    Index => index: i32,
+
+    /// The name of the item
+    Name => name: String,
+
+    /// The id of the item as a hexadecimal string. This id does not change over time.
+    PersistentId as "persistentID" => persistent_id: String,
+
+    /// The album name of the track
+    Album => album: String,
+
+    /// The album artist of the track
+    AlbumArtist => album_artist: String,
+
+    /// Is the album for this track disliked?
+    AlbumDisliked => album_disliked: bool,
+
+    /// Is the album for this track favorited?
+    AlbumFavorited => album_favorited: bool,
+
+    /// The rating of the album for this track (0 to 100)
+    AlbumRating => album_rating: i16,
+
+    /// The rating kind of the album rating for this track
+    AlbumRatingKind => album_rating_kind: Kind,
+
+    /// The artist of the track
+    Artist => artist: String,
+
+    /// The bit rate of the track (in kbps)
+    BitRate => bit_rate: i16,
+
+    /// The bookmark time of the track in seconds
+    Bookmark => bookmark: f64,
+
+    /// Is the playback position for this track remembered?
+    Bookmarkable => bookmarkable: bool,
+
+    /// The tempo of this track in beats per minute
+    Bpm => bpm: i16,
+
+    /// The category of the track
+    Category => category: String,
+
+    /// The iCloud status of the track
+    CloudStatus => cloud_status: CloudStatus,
+
+    /// Freeform notes about the track
+    Comment => comment: String,
+
+    /// Is this track from a compilation album?
+    Compilation => compilation: bool,
+
+    /// The composer of the track
+    Composer => composer: String,
+
+    /// The common, unique ID for this track. If two tracks in different playlists have the same database ID, they are sharing the same data.
+    DatabaseId as "databaseID" => database_id: i32,
+
+    /// The date the track was added to the playlist
+    DateAdded => date_added: String,
+
+    /// The description of the track
+    Description => description: String,
+
+    /// The total number of discs in the source album
+    DiscCount => disc_count: i16,
+
+    /// The index of the disc containing this track on the source album
+    DiscNumber => disc_number: i16,
+
+    /// Is this track disliked?
+    Disliked => disliked: bool,
+
+    /// The Apple ID of the person who downloaded this track
+    DownloaderAppleId as "downloaderAppleID" => downloader_apple_id: String,
+
+    /// The name of the person who downloaded this track
+    DownloaderName => downloader_name: String,
+
+    /// The length of the track in seconds
+    Duration => duration: f64,
+
+    /// Is this track checked for playback?
+    Enabled => enabled: bool,
+
+    /// The episode ID of the track
+    EpisodeId as "episodeID" => episode_id: String,
+
+    /// The episode number of the track
+    EpisodeNumber => episode_number: i16,
+
+    /// The name of the EQ preset of the track
+    Eq => eq: String,
+
+    /// The stop time of the track in seconds
+    Finish => finish: f64,
+
+    /// Is this track from a gapless album?
+    Gapless => gapless: bool,
+
+    /// The music/audio genre (category) of the track
+    Genre => genre: String,
+
+    /// The grouping (piece) of the track. Generally used to denote movements within a classical work.
+    Grouping => grouping: String,
+
+    /// A text description of the track
+    Kind => kind: String,
+
+    /// The long description of the track
+    LongDescription => long_description: String,
+
+    /// Is this track favorited?
+    Favorited => favorited: bool,
+
+    /// The lyrics of the track
+    Lyrics => lyrics: String,
+
+    /// The media kind of the track
+    MediaKind => media_kind: MediaKind,
+
+    /// The modification date of the content of this track
+    ModificationDate => modification_date: String,
+
+    /// The movement name of the track
+    Movement => movement: String,
+
+    /// The total number of movements in the work
+    MovementCount => movement_count: i16,
+
+    /// The index of the movement in the work
+    MovementNumber => movement_number: i16,
+
+    /// Number of times this track has been played
+    PlayedCount => played_count: i16,
+
+    /// The date and time this track was last played
+    PlayedDate => played_date: String,
+
+    /// The Apple ID of the person who purchased this track
+    PurchaserAppleId as "purchaserAppleID" => purchaser_apple_id: String,
+
+    /// The name of the person who purchased this track
+    PurchaserName => purchaser_name: String,
+
+    /// The rating of this track (0 to 100)
+    Rating => rating: i16,
+
+    /// The rating kind of this track
+    RatingKind => rating_kind: Kind,
+
+    /// The release date of this track
+    ReleaseDate => release_date: String,
+
+    /// The sample rate of the track (in Hz)
+    SampleRate => sample_rate: i32,
+
+    /// The season number of the track
+    SeasonNumber => season_number: i16,
+
+    /// Is this track included when shuffling?
+    Shufflable => shufflable: bool,
+
+    /// Number of times this track has been skipped
+    SkippedCount => skipped_count: i16,
+
+    /// The date and time this track was last skipped
+    SkippedDate => skipped_date: String,
+
+    /// The show name of the track
+    Show => show: String,
+
+    /// Override string to use for the track when sorting by album
+    SortAlbum => sort_album: String,
+
+    /// Override string to use for the track when sorting by artist
+    SortArtist => sort_artist: String,
+
+    /// Override string to use for the track when sorting by album artist
+    SortAlbumArtist => sort_album_artist: String,
+
+    /// Override string to use for the track when sorting by name
+    SortName => sort_name: String,
+
+    /// Override string to use for the track when sorting by composer
+    SortComposer => sort_composer: String,
+
+    /// Override string to use for the track when sorting by show name
+    SortShow => sort_show: String,
+
+    /// The size of the track (in bytes)
+    Size => size: i64,
+
+    /// The start time of the track in seconds
+    Start => start: f64,
+
+    /// The length of the track in MM:SS format
+    Time => time: String,
+
+    /// The total number of tracks on the source album
+    TrackCount => track_count: i16,
+
+    /// The index of the track on the source album
+    TrackNumber => track_number: i16,
+
+    /// Is this track unplayed?
+    Unplayed => unplayed: bool,
+
+    /// Relative volume adjustment of the track (-100% to 100%)
+    VolumeAdjustment => volume_adjustment: i16,
+
+    /// The work name of the track
+    Work => work: String,
+
+    /// The year the track was recorded/released
+    Year => year: i16,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn uses_property_names() {
+        for (field, name) in [
+            (TrackField::Name, "name"),
+            (TrackField::AlbumArtist, "albumArtist"),
+            (TrackField::PersistentId, "persistentID"),
+            (TrackField::DatabaseId, "databaseID"),
+        ] {
+            assert_eq!(field.to_string(), name);
+            assert_eq!(serde_json::to_value(field).unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn reads_selected_fields() {
+        let track: PartialTrack = serde_json::from_str(
+            r#"{"name": "One More Time", "persistentID": "ABC", "albumArtist": "Daft Punk"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(track.name.as_deref(), Some("One More Time"));
+        assert_eq!(track.persistent_id.as_deref(), Some("ABC"));
+        assert_eq!(track.album_artist.as_deref(), Some("Daft Punk"));
+        assert_eq!(track.album, None);
+    }
+
+    #[test]
+    fn reads_fractional_bookmarks() {
+        let track: PartialTrack = serde_json::from_str(r#"{"bookmark": 754.25}"#).unwrap();
+        assert_eq!(track.bookmark, Some(754.25));
+    }
+}
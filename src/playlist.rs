@@ -1,5 +1,6 @@
+use crate::client::AppleMusicClient;
 use crate::error::Error;
-use crate::script_controller::{ParamType, ScriptController};
+use crate::partial_track::{PartialTrack, TrackField};
 use crate::track::Track;
 use serde::Deserialize;
 
@@ -55,56 +56,37 @@ pub struct Playlist {
 }
 
 impl Playlist {
-    /// Fetches Tracks in Playlist and populates `self.tracks`.
-    /// WARNING: This fails when more than 900 tracks are fetched due to a JavaScript limit.
+    /// Fetches Tracks in Playlist and populates `self.tracks`, `DEFAULT_PAGE_SIZE` Tracks per script.
     pub fn fetch_playlist_tracks(&mut self) -> Result<(), Error> {
-        match ScriptController.execute_script::<Vec<Track>>(
-            ParamType::PlaylistTracks,
-            Some(self.id),
-            None,
-        ) {
-            Ok(data) => {
-                self.tracks = Some(data);
-                Ok(())
-            }
-            Err(err) => Err(err),
-        }
+        self.tracks = Some(AppleMusicClient::default().fetch_playlist_tracks(&*self)?);
+        Ok(())
+    }
+
+    /// Fetches and returns the Tracks of the Playlist, only holding the provided fields.
+    pub fn fetch_partial_tracks(&self, fields: &[TrackField]) -> Result<Vec<PartialTrack>, Error> {
+        AppleMusicClient::default().fetch_playlist_partial_tracks(self, fields)
+    }
+
+    /// Returns true if the Playlist still exists in Apple Music.
+    pub fn exists(&self) -> Result<bool, Error> {
+        AppleMusicClient::default().playlist_exists(self.id)
     }
 
     /// Searches inside a Playlist for Tracks containing provided query and returns them.
     pub fn search_for_tracks(&self, query: &str) -> Result<Option<Vec<Track>>, Error> {
-        match ScriptController.execute_script::<Vec<Track>>(
-            ParamType::SearchInPlaylist,
-            Some(self.id),
-            Some(query),
-        ) {
-            Ok(data) => Ok(Option::from(data)),
-            Err(err) => Err(err),
-        }
+        AppleMusicClient::default()
+            .search_in_playlist(self, query)
+            .map(Some)
     }
 
     /// Reveals and selects Playlist in Apple Music player.
     pub fn reveal_in_player(&self) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').reveal(Application('Music').playlists.byId({}))",
-            self.id
-        );
-
-        let _ = ScriptController.execute(cmd.as_str(), None);
-
-        Ok(())
+        AppleMusicClient::default().reveal_playlist(self)
     }
 
     /// Triggers a download on Apple Music Player for the Playlist.
     pub fn download(&self) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').download(Application('Music').playlists.byId({}))",
-            self.id
-        );
-
-        let _ = ScriptController.execute(cmd.as_str(), None);
-
-        Ok(())
+        AppleMusicClient::default().download_playlist(self)
     }
 }
 
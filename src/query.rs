@@ -0,0 +1,586 @@
+#[cfg(feature = "async")]
+use crate::async_client::{AsyncAppleMusicClient, AsyncTrackPages};
+use crate::calls;
+use crate::client::AppleMusicClient;
+use crate::editor::BulkTrackEditor;
+use crate::error::Error;
+use crate::id::PlaylistId;
+use crate::jxa::Jxa;
+use crate::paging::TrackPages;
+use crate::partial_track::{PartialTrack, TrackField};
+use crate::playlist::Playlist;
+use crate::script_controller::{ParamType, ScriptRequest};
+use crate::selection::{TrackResult, TrackSelection};
+use crate::track::Track;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::fmt::Display;
+use strum_macros::Display;
+
+/// Comparison applied by a `Filter` between a property and a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Equals,
+    NotEquals,
+    Contains,
+    NotContains,
+    BeginsWith,
+    EndsWith,
+    GreaterThan,
+    GreaterThanOrEquals,
+    LessThan,
+    LessThanOrEquals,
+}
+
+/// Playlist property which can be used in a `Filter`.
+#[derive(Display, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[strum(serialize_all = "camelCase")]
+pub enum PlaylistField {
+    Class,
+    Id,
+    Index,
+    Name,
+    #[strum(serialize = "persistentID")]
+    PersistentId,
+    Description,
+    Disliked,
+    Duration,
+    Favorited,
+    Size,
+    SpecialKind,
+    Time,
+    Visible,
+}
+
+/// Condition evaluated by Apple Music itself, compiled into a JXA `whose({...})` clause.
+///
+/// e.g. all Tracks by an artist which are either favorited or rated 80 and more:
+/// ```rust
+/// use apple_music::{Filter, Operator, TrackField};
+///
+/// let filter = Filter::new(TrackField::Artist, Operator::Equals, "Daft Punk")?.and(
+///     Filter::new(TrackField::Favorited, Operator::Equals, true)?
+///         .or(Filter::new(TrackField::Rating, Operator::GreaterThanOrEquals, 80)?),
+/// );
+///
+/// assert_eq!(
+///     filter.to_whose().to_string(),
+///     r#"{"_and":[{"artist":{"_equals":"Daft Punk"}},{"_or":[{"favorited":{"_equals":true}},{"rating":{"_greaterThanEquals":80}}]}]}"#
+/// );
+/// # Ok::<(), apple_music::Error>(())
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter<F> {
+    /// Compares a property to a value
+    Condition(F, Operator, Value),
+
+    /// Matches if every Filter matches
+    All(Vec<Filter<F>>),
+
+    /// Matches if any Filter matches
+    Any(Vec<Filter<F>>),
+
+    /// Matches if the Filter does not match
+    Not(Box<Filter<F>>),
+}
+
+impl<F: Display> Filter<F> {
+    /// Creates a Filter comparing the provided property to the provided value.
+    /// Returns an `Error::InvalidArgument` if the value cannot be represented in JSON, e.g. a map with non-string keys.
+    pub fn new<T: Serialize>(field: F, operator: Operator, value: T) -> Result<Self, Error> {
+        let value = filter_value(&field, value).map_err(Error::InvalidArgument)?;
+        Ok(Filter::Condition(field, operator, value))
+    }
+
+    /// Returns a Filter matching if both this Filter and the provided one match.
+    pub fn and(self, other: Filter<F>) -> Self {
+        match self {
+            Filter::All(mut filters) => {
+                filters.push(other);
+                Filter::All(filters)
+            }
+            filter => Filter::All(vec![filter, other]),
+        }
+    }
+
+    /// Returns a Filter matching if either this Filter or the provided one matches.
+    pub fn or(self, other: Filter<F>) -> Self {
+        match self {
+            Filter::Any(mut filters) => {
+                filters.push(other);
+                Filter::Any(filters)
+            }
+            filter => Filter::Any(vec![filter, other]),
+        }
+    }
+
+    /// Returns a Filter matching if this Filter does not.
+    pub fn negate(self) -> Self {
+        Filter::Not(Box::new(self))
+    }
+
+    /// Returns the JXA `whose` clause of the Filter.
+    pub fn to_whose(&self) -> Value {
+        match self {
+            Filter::Condition(field, operator, value) => {
+                let (operator, negated) = match operator {
+                    Operator::Equals => ("_equals", false),
+                    Operator::NotEquals => ("_equals", true),
+                    Operator::Contains => ("_contains", false),
+                    Operator::NotContains => ("_contains", true),
+                    Operator::BeginsWith => ("_beginsWith", false),
+                    Operator::EndsWith => ("_endsWith", false),
+                    Operator::GreaterThan => ("_greaterThan", false),
+                    Operator::GreaterThanOrEquals => ("_greaterThanEquals", false),
+                    Operator::LessThan => ("_lessThan", false),
+                    Operator::LessThanOrEquals => ("_lessThanEquals", false),
+                };
+
+                let mut condition = serde_json::Map::new();
+                condition.insert(field.to_string(), json!({ operator: value }));
+
+                if negated {
+                    json!({ "_not": [condition] })
+                } else {
+                    Value::Object(condition)
+                }
+            }
+            Filter::All(filters) => {
+                json!({ "_and": filters.iter().map(Filter::to_whose).collect::<Vec<_>>() })
+            }
+            Filter::Any(filters) => {
+                json!({ "_or": filters.iter().map(Filter::to_whose).collect::<Vec<_>>() })
+            }
+            Filter::Not(filter) => json!({ "_not": [filter.to_whose()] }),
+        }
+    }
+}
+
+/// Returns the JSON value compared by a Filter, or why the provided value cannot be one.
+fn filter_value<F: Display, T: Serialize>(field: &F, value: T) -> Result<Value, String> {
+    serde_json::to_value(value)
+        .map_err(|err| format!("the value compared to {} is not valid JSON: {}", field, err))
+}
+
+/// Query returning the Tracks matching every provided Filter, evaluated inside Apple Music.
+///
+/// Created through `AppleMusicClient::tracks()`:
+/// ```rust,ignore
+/// let tracks = client
+///     .tracks()
+///     .filter(TrackField::Artist, Operator::Equals, "Daft Punk")
+///     .filter(TrackField::Rating, Operator::GreaterThanOrEquals, 80)
+///     .execute()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrackQuery<C> {
+    client: C,
+    playlist: Option<PlaylistId>,
+    filters: Vec<Filter<TrackField>>,
+    invalid: Option<String>,
+}
+
+impl<C> TrackQuery<C> {
+    pub(crate) fn new(client: C) -> Self {
+        TrackQuery {
+            client,
+            playlist: None,
+            filters: Vec::new(),
+            invalid: None,
+        }
+    }
+
+    /// Only keeps Tracks whose field matches the provided value.
+    /// A value which cannot be represented in JSON fails the query with an `Error::InvalidArgument`.
+    pub fn filter<T: Serialize>(mut self, field: TrackField, operator: Operator, value: T) -> Self {
+        match filter_value(&field, value) {
+            Ok(value) => self.matching(Filter::Condition(field, operator, value)),
+            Err(invalid) => {
+                self.invalid.get_or_insert(invalid);
+                self
+            }
+        }
+    }
+
+    /// Only keeps Tracks matching the provided Filter.
+    pub fn matching(mut self, filter: Filter<TrackField>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Only looks for Tracks inside the provided Playlist, instead of the whole library.
+    pub fn in_playlist<P: Into<PlaylistId>>(mut self, playlist: P) -> Self {
+        self.playlist = Some(playlist.into());
+        self
+    }
+
+    /// Returns the Filter combining every condition of the query, if any.
+    pub fn to_filter(&self) -> Option<Filter<TrackField>> {
+        match self.filters.as_slice() {
+            [] => None,
+            [filter] => Some(filter.clone()),
+            filters => Some(Filter::All(filters.to_vec())),
+        }
+    }
+
+    /// Returns an `Error::InvalidArgument` if a filter value could not be represented in JSON.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        match &self.invalid {
+            Some(invalid) => Err(Error::InvalidArgument(invalid.clone())),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn to_request(&self, param_type: ParamType) -> Result<ScriptRequest, Error> {
+        self.validate()?;
+        let mut request = ScriptRequest::new(param_type);
+
+        if let Some(playlist) = &self.playlist {
+            request = request.playlist(playlist);
+        }
+
+        Ok(match self.to_filter() {
+            Some(filter) => request.filter(filter.to_whose()),
+            None => request,
+        })
+    }
+
+    /// Returns the selection of matching Tracks, for bulk operations.
+    pub(crate) fn selection(&self, app: &Jxa) -> TrackSelection {
+        TrackSelection::query(self.specifier(app))
+    }
+
+    /// Returns the specifier of matching Tracks, e.g. `Application("Music").tracks.whose({...})`.
+    pub(crate) fn specifier(&self, app: &Jxa) -> Jxa {
+        let tracks = match &self.playlist {
+            Some(playlist) => playlist.specifier(app).property("tracks"),
+            None => app.property("tracks"),
+        };
+
+        match self.to_filter() {
+            Some(filter) => tracks.call("whose", &[Jxa::json(&filter.to_whose())]),
+            None => tracks,
+        }
+    }
+}
+
+impl TrackQuery<&AppleMusicClient> {
+    /// Returns a new BulkTrackEditor, collecting changes to apply to every matching Track in a single script.
+    pub fn edit(&self) -> BulkTrackEditor {
+        BulkTrackEditor::for_selection(self.client.clone(), self.selection(&self.client.app()))
+            .invalidate(self.invalid.clone())
+    }
+
+    /// Updates every matching file Track from the metadata of its file, in a single script, and returns their results.
+    pub fn refresh(&self) -> Result<Vec<TrackResult>, Error> {
+        self.validate()?;
+        self.client.refresh(self.selection(&self.client.app()))
+    }
+
+    /// Returns every matching Track.
+    pub fn execute(&self) -> Result<Vec<Track>, Error> {
+        self.client.call(calls::page_tracks(
+            self.to_request(ParamType::FilterTracks)?,
+        ))
+    }
+
+    /// Returns every matching Track, only holding the provided fields.
+    pub fn execute_partial(&self, fields: &[TrackField]) -> Result<Vec<PartialTrack>, Error> {
+        let request = self.to_request(ParamType::FilterTracks)?.fields(fields);
+        self.client.call(calls::page_tracks(request))
+    }
+
+    /// Returns an iterator fetching matching Tracks page by page, `page_size` Tracks per script.
+    pub fn pages(&self, page_size: usize) -> Result<TrackPages, Error> {
+        Ok(TrackPages::new(
+            self.client.clone(),
+            self.to_request(ParamType::FilterTracks)?,
+            page_size,
+        ))
+    }
+
+    /// Returns the number of matching Tracks, without fetching them.
+    pub fn count(&self) -> Result<usize, Error> {
+        self.client
+            .execute_request(&self.to_request(ParamType::CountTracks)?)
+    }
+
+    /// Returns true if at least one Track matches, without fetching it.
+    pub fn exists(&self) -> Result<bool, Error> {
+        Ok(self.count()? > 0)
+    }
+}
+
+/// Query returning the Playlists matching every provided Filter, evaluated inside Apple Music.
+///
+/// Created through `AppleMusicClient::playlists()`.
+#[derive(Debug, Clone)]
+pub struct PlaylistQuery<C> {
+    client: C,
+    filters: Vec<Filter<PlaylistField>>,
+    invalid: Option<String>,
+}
+
+impl<C> PlaylistQuery<C> {
+    pub(crate) fn new(client: C) -> Self {
+        PlaylistQuery {
+            client,
+            filters: Vec::new(),
+            invalid: None,
+        }
+    }
+
+    /// Only keeps Playlists whose field matches the provided value.
+    /// A value which cannot be represented in JSON fails the query with an `Error::InvalidArgument`.
+    pub fn filter<T: Serialize>(
+        mut self,
+        field: PlaylistField,
+        operator: Operator,
+        value: T,
+    ) -> Self {
+        match filter_value(&field, value) {
+            Ok(value) => self.matching(Filter::Condition(field, operator, value)),
+            Err(invalid) => {
+                self.invalid.get_or_insert(invalid);
+                self
+            }
+        }
+    }
+
+    /// Only keeps Playlists matching the provided Filter.
+    pub fn matching(mut self, filter: Filter<PlaylistField>) -> Self {
+        self.filters.push(filter);
+        self
+    }
+
+    /// Returns the Filter combining every condition of the query, if any.
+    pub fn to_filter(&self) -> Option<Filter<PlaylistField>> {
+        match self.filters.as_slice() {
+            [] => None,
+            [filter] => Some(filter.clone()),
+            filters => Some(Filter::All(filters.to_vec())),
+        }
+    }
+
+    /// Returns an `Error::InvalidArgument` if a filter value could not be represented in JSON.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        match &self.invalid {
+            Some(invalid) => Err(Error::InvalidArgument(invalid.clone())),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn to_request(&self, param_type: ParamType) -> Result<ScriptRequest, Error> {
+        self.validate()?;
+        let request = ScriptRequest::new(param_type);
+
+        Ok(match self.to_filter() {
+            Some(filter) => request.filter(filter.to_whose()),
+            None => request,
+        })
+    }
+}
+
+impl PlaylistQuery<&AppleMusicClient> {
+    /// Returns every matching Playlist.
+    pub fn execute(&self) -> Result<Vec<Playlist>, Error> {
+        self.client
+            .execute_request(&self.to_request(ParamType::FilterPlaylists)?)
+    }
+
+    /// Returns the number of matching Playlists, without fetching them.
+    pub fn count(&self) -> Result<usize, Error> {
+        self.client
+            .execute_request(&self.to_request(ParamType::CountPlaylists)?)
+    }
+
+    /// Returns true if at least one Playlist matches, without fetching it.
+    pub fn exists(&self) -> Result<bool, Error> {
+        Ok(self.count()? > 0)
+    }
+}
+
+#[cfg(feature = "async")]
+impl TrackQuery<&AsyncAppleMusicClient> {
+    /// Returns a new BulkTrackEditor, collecting changes to apply to every matching Track in a single script.
+    pub fn edit(&self) -> BulkTrackEditor<AsyncAppleMusicClient> {
+        BulkTrackEditor::for_selection(self.client.clone(), self.selection(&self.client.app()))
+            .invalidate(self.invalid.clone())
+    }
+
+    /// Updates every matching file Track from the metadata of its file, in a single script, and returns their results.
+    pub async fn refresh(&self) -> Result<Vec<TrackResult>, Error> {
+        self.validate()?;
+        self.client
+            .refresh(self.selection(&self.client.app()))
+            .await
+    }
+
+    /// Returns every matching Track.
+    pub async fn execute(&self) -> Result<Vec<Track>, Error> {
+        self.client
+            .call(calls::page_tracks(
+                self.to_request(ParamType::FilterTracks)?,
+            ))
+            .await
+    }
+
+    /// Returns every matching Track, only holding the provided fields.
+    pub async fn execute_partial(&self, fields: &[TrackField]) -> Result<Vec<PartialTrack>, Error> {
+        let request = self.to_request(ParamType::FilterTracks)?.fields(fields);
+        self.client.call(calls::page_tracks(request)).await
+    }
+
+    /// Returns a cursor fetching matching Tracks page by page, `page_size` Tracks per script.
+    pub fn pages(&self, page_size: usize) -> Result<AsyncTrackPages, Error> {
+        Ok(AsyncTrackPages::new(
+            self.client.clone(),
+            self.to_request(ParamType::FilterTracks)?,
+            page_size,
+        ))
+    }
+
+    /// Returns the number of matching Tracks, without fetching them.
+    pub async fn count(&self) -> Result<usize, Error> {
+        self.client
+            .execute_request(&self.to_request(ParamType::CountTracks)?)
+            .await
+    }
+
+    /// Returns true if at least one Track matches, without fetching it.
+    pub async fn exists(&self) -> Result<bool, Error> {
+        Ok(self.count().await? > 0)
+    }
+}
+
+#[cfg(feature = "async")]
+impl PlaylistQuery<&AsyncAppleMusicClient> {
+    /// Returns every matching Playlist.
+    pub async fn execute(&self) -> Result<Vec<Playlist>, Error> {
+        self.client
+            .execute_request(&self.to_request(ParamType::FilterPlaylists)?)
+            .await
+    }
+
+    /// Returns the number of matching Playlists, without fetching them.
+    pub async fn count(&self) -> Result<usize, Error> {
+        self.client
+            .execute_request(&self.to_request(ParamType::CountPlaylists)?)
+            .await
+    }
+
+    /// Returns true if at least one Playlist matches, without fetching it.
+    pub async fn exists(&self) -> Result<bool, Error> {
+        Ok(self.count().await? > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn condition(field: TrackField, operator: Operator, value: i32) -> Filter<TrackField> {
+        Filter::new(field, operator, value).unwrap()
+    }
+
+    fn invalid_value() -> HashMap<(i32, i32), i32> {
+        HashMap::from([((1, 2), 3)])
+    }
+
+    #[test]
+    fn negates_not_operators() {
+        let filter = Filter::new(TrackField::Name, Operator::NotEquals, "Intro").unwrap();
+        assert_eq!(
+            filter.to_whose(),
+            json!({"_not": [{"name": {"_equals": "Intro"}}]})
+        );
+
+        let filter = Filter::new(TrackField::Genre, Operator::NotContains, "Rock").unwrap();
+        assert_eq!(
+            filter.to_whose(),
+            json!({"_not": [{"genre": {"_contains": "Rock"}}]})
+        );
+    }
+
+    #[test]
+    fn negates_nested_filters() {
+        let filter = condition(TrackField::Rating, Operator::LessThan, 20)
+            .or(condition(TrackField::PlayedCount, Operator::Equals, 0))
+            .negate();
+
+        assert_eq!(
+            filter.to_whose(),
+            json!({"_not": [{"_or": [
+                {"rating": {"_lessThan": 20}},
+                {"playedCount": {"_equals": 0}},
+            ]}]})
+        );
+    }
+
+    #[test]
+    fn nests_and_inside_or() {
+        let filter = condition(TrackField::Year, Operator::GreaterThanOrEquals, 1990)
+            .and(condition(
+                TrackField::Year,
+                Operator::LessThanOrEquals,
+                1999,
+            ))
+            .and(condition(TrackField::Rating, Operator::GreaterThan, 60))
+            .or(condition(
+                TrackField::PlayedCount,
+                Operator::GreaterThan,
+                100,
+            ));
+
+        assert_eq!(
+            filter.to_whose(),
+            json!({"_or": [
+                {"_and": [
+                    {"year": {"_greaterThanEquals": 1990}},
+                    {"year": {"_lessThanEquals": 1999}},
+                    {"rating": {"_greaterThan": 60}},
+                ]},
+                {"playedCount": {"_greaterThan": 100}},
+            ]})
+        );
+    }
+
+    #[test]
+    fn combines_query_filters() {
+        let query = TrackQuery::new(())
+            .filter(TrackField::Artist, Operator::BeginsWith, "Daft")
+            .filter(TrackField::Name, Operator::EndsWith, "(Live)");
+
+        assert_eq!(
+            query.to_filter().unwrap().to_whose(),
+            json!({"_and": [
+                {"artist": {"_beginsWith": "Daft"}},
+                {"name": {"_endsWith": "(Live)"}},
+            ]})
+        );
+    }
+
+    #[test]
+    fn rejects_values_which_are_not_json() {
+        assert!(matches!(
+            Filter::new(TrackField::Name, Operator::Equals, invalid_value()),
+            Err(Error::InvalidArgument(_))
+        ));
+
+        let query = TrackQuery::new(())
+            .filter(TrackField::Name, Operator::Equals, invalid_value())
+            .filter(TrackField::Rating, Operator::Equals, 100);
+        assert!(matches!(
+            query.to_request(ParamType::FilterTracks),
+            Err(Error::InvalidArgument(_))
+        ));
+
+        let query =
+            PlaylistQuery::new(()).filter(PlaylistField::Name, Operator::Equals, invalid_value());
+        assert!(matches!(
+            query.to_request(ParamType::FilterPlaylists),
+            Err(Error::InvalidArgument(_))
+        ));
+    }
+}
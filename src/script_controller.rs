@@ -1,12 +1,24 @@
-use crate::error::Error;
-use log::error;
-use serde::{Deserialize, Serialize};
-use serde_json::json;
-use std::collections::HashMap;
-use std::process::{Command, Output};
+use crate::error::{Error, ScriptError};
+use crate::id::{PlaylistId, TrackId};
+use crate::partial_track::TrackField;
+use crate::search::SearchKind;
+use serde::Serialize;
+use serde_json::Value;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 use strum_macros::Display;
 
-#[derive(Serialize, Display)]
+/// JavaScript file embedded in the crate, handling every typed `ScriptRequest`.
+pub const SCRIPT: &str = include_str!("scripts/script.js");
+
+/// Kind of data requested from `scripts/script.js`.
+#[derive(Serialize, Display, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
 #[strum(serialize_all = "camelCase")]
 pub enum ParamType {
     Artworks,
@@ -16,77 +28,531 @@ pub enum ParamType {
     PlaylistTracks,
     ApplicationData,
     SearchInPlaylist,
+    Search,
+    FilterTracks,
+    FilterPlaylists,
+    CountTracks,
+    CountPlaylists,
+    TrackExists,
+    PlaylistExists,
+    TrackById,
+    TrackByPersistentId,
+    TracksByIds,
+    PlaylistByPersistentId,
+    Add,
+    FileTrackLocations,
 }
 
-/// TEST
-pub struct ScriptController;
+/// Typed request sent to `scripts/script.js`, serialized as JSON.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ScriptRequest {
+    param_type: ParamType,
 
-impl ScriptController {
-    pub fn execute_script<T>(
-        &self,
-        param_type: ParamType,
-        id: Option<i32>,
-        query: Option<&str>,
-    ) -> Result<T, Error>
-    where
-        T: for<'a> Deserialize<'a>,
-    {
-        let params = self.generate_json(param_type, id, query);
-        let script_path = include_str!("scripts/script.js");
-
-        let output = self.execute(script_path, Some(params));
-
-        let data;
-        match output {
-            Ok(d) => data = d,
-            Err(err) => {
-                error!("{:#?}", err);
-                return Err(err);
+    #[serde(skip_serializing_if = "Option::is_none")]
+    app: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    id: Option<i32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    persistent_id: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ids: Option<Vec<i32>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    persistent_ids: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    paths: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    query: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<SearchKind>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<Vec<TrackField>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    offset: Option<usize>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<usize>,
+}
+
+impl ScriptRequest {
+    /// Creates a new request for the provided ParamType.
+    pub fn new(param_type: ParamType) -> Self {
+        ScriptRequest {
+            param_type,
+            app: None,
+            id: None,
+            persistent_id: None,
+            ids: None,
+            persistent_ids: None,
+            paths: None,
+            query: None,
+            kind: None,
+            source: None,
+            filter: None,
+            fields: None,
+            offset: None,
+            limit: None,
+        }
+    }
+
+    /// Sets the name of the targeted application. Defaults to `Music` when unset.
+    pub fn app(mut self, app: &str) -> Self {
+        self.app = Some(app.into());
+        self
+    }
+
+    /// Sets the id of the targeted object.
+    pub fn id(mut self, id: i32) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Sets the persistent ID of the targeted object.
+    pub fn persistent_id(mut self, persistent_id: &str) -> Self {
+        self.persistent_id = Some(persistent_id.into());
+        self
+    }
+
+    /// Sets the ids of the targeted objects.
+    pub fn ids(mut self, ids: &[i32]) -> Self {
+        self.ids = Some(ids.to_vec());
+        self
+    }
+
+    /// Sets the persistent IDs of the targeted objects.
+    pub fn persistent_ids<S: AsRef<str>>(mut self, persistent_ids: &[S]) -> Self {
+        self.persistent_ids = Some(persistent_ids.iter().map(|id| id.as_ref().into()).collect());
+        self
+    }
+
+    /// Sets the absolute paths of the targeted files & folders.
+    pub fn paths<S: AsRef<str>>(mut self, paths: &[S]) -> Self {
+        self.paths = Some(paths.iter().map(|path| path.as_ref().into()).collect());
+        self
+    }
+
+    /// Sets the id, or the persistent ID, of the targeted Track.
+    pub fn track(self, track: &TrackId) -> Self {
+        match track {
+            TrackId::Id(id) => self.id(*id),
+            TrackId::PersistentId(persistent_id) => self.persistent_id(persistent_id),
+        }
+    }
+
+    /// Sets the id, or the persistent ID, of the targeted Playlist.
+    pub fn playlist(self, playlist: &PlaylistId) -> Self {
+        match playlist {
+            PlaylistId::Id(id) => self.id(*id),
+            PlaylistId::PersistentId(persistent_id) => self.persistent_id(persistent_id),
+        }
+    }
+
+    /// Sets the query string of the request.
+    pub fn query(mut self, query: &str) -> Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    /// Sets the kind of search run by Apple Music.
+    pub fn kind(mut self, kind: SearchKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Sets the name of the targeted source.
+    pub fn source(mut self, source: &str) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Sets the JXA `whose` clause selecting the returned objects, e.g. `Filter::to_whose()`.
+    pub fn filter(mut self, filter: Value) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Restricts the returned Tracks to the provided fields.
+    pub fn fields(mut self, fields: &[TrackField]) -> Self {
+        self.fields = Some(fields.to_vec());
+        self
+    }
+
+    /// Restricts the request to `limit` items, starting at index `offset`.
+    pub fn page(mut self, offset: usize, limit: usize) -> Self {
+        self.offset = Some(offset);
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Returns the ParamType of the request.
+    pub fn param_type(&self) -> ParamType {
+        self.param_type
+    }
+
+    /// Returns the name of the targeted application, if set.
+    pub fn app_name(&self) -> Option<&str> {
+        self.app.as_deref()
+    }
+
+    /// Returns the id of the targeted object, if any.
+    pub fn target_id(&self) -> Option<i32> {
+        self.id
+    }
+
+    /// Returns the persistent ID of the targeted object, if any.
+    pub fn target_persistent_id(&self) -> Option<&str> {
+        self.persistent_id.as_deref()
+    }
+
+    /// Returns the ids of the targeted objects, if any.
+    pub fn target_ids(&self) -> Option<&[i32]> {
+        self.ids.as_deref()
+    }
+
+    /// Returns the persistent IDs of the targeted objects, if any.
+    pub fn target_persistent_ids(&self) -> Option<&[String]> {
+        self.persistent_ids.as_deref()
+    }
+
+    /// Returns the paths of the targeted files & folders, if any.
+    pub fn target_paths(&self) -> Option<&[String]> {
+        self.paths.as_deref()
+    }
+
+    /// Returns the query string of the request, if any.
+    pub fn query_str(&self) -> Option<&str> {
+        self.query.as_deref()
+    }
+
+    /// Returns the kind of search of the request, if any.
+    pub fn search_kind(&self) -> Option<SearchKind> {
+        self.kind
+    }
+
+    /// Returns the name of the targeted source, if any.
+    pub fn source_name(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    /// Returns the JXA `whose` clause of the request, if any.
+    pub fn whose_filter(&self) -> Option<&Value> {
+        self.filter.as_ref()
+    }
+
+    /// Returns the selected Track fields of the request, if any.
+    pub fn track_fields(&self) -> Option<&[TrackField]> {
+        self.fields.as_deref()
+    }
+
+    /// Returns the `(offset, limit)` page of the request, if any.
+    pub fn page_bounds(&self) -> Option<(usize, usize)> {
+        self.offset.zip(self.limit)
+    }
+
+    /// Returns the JSON representation of the request, as expected by `scripts/script.js`.
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string(self).map_err(Error::Serialization)
+    }
+}
+
+/// Options applied to a single script execution.
+#[derive(Debug, Clone, Default)]
+pub struct ExecutionOptions {
+    /// Maximum duration of the script, after which it is killed and `Error::Timeout` is returned
+    pub timeout: Option<Duration>,
+
+    /// Token allowing to abort the script from another thread, returning `Error::Cancelled`
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl ExecutionOptions {
+    /// Returns an error if the script started at `start` must be aborted.
+    pub fn check(&self, start: Instant) -> Result<(), Error> {
+        if let Some(token) = &self.cancellation {
+            if token.is_cancelled() {
+                return Err(Error::Cancelled);
             }
         }
 
-        let output_str = String::from_utf8_lossy(&data.stdout);
+        if let Some(timeout) = self.timeout {
+            if start.elapsed() >= timeout {
+                return Err(Error::Timeout(timeout));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns true if a script run with these options may need to be aborted.
+    pub fn is_bounded(&self) -> bool {
+        self.timeout.is_some() || self.cancellation.is_some()
+    }
+}
+
+/// Token used to cancel running scripts, e.g. a long `get_all_library_tracks` call.
+///
+/// Clones share the same state: cancelling one cancels all of them.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Creates a new, non-cancelled token.
+    pub fn new() -> Self {
+        CancellationToken::default()
+    }
+
+    /// Cancels every script running with this token, as well as all future ones.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Returns true if the token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Backend in charge of running JavaScript for Automation (JXA) scripts against Apple Music.
+///
+/// `ScriptController` is the default implementation, relying on `osascript`.
+/// Any other implementation (mocks, recorders, remote transports...) can be injected
+/// into an `AppleMusicClient` through `AppleMusicClient::with_backend`.
+pub trait ScriptBackend: Send + Sync {
+    /// Executes a raw JXA script, with optional parameters, and returns its output.
+    fn execute(
+        &self,
+        script: &str,
+        params: Option<&str>,
+        options: &ExecutionOptions,
+    ) -> Result<String, Error>;
+
+    /// Executes a typed request against `scripts/script.js` and returns its raw JSON output.
+    fn execute_request(
+        &self,
+        request: &ScriptRequest,
+        options: &ExecutionOptions,
+    ) -> Result<String, Error> {
+        self.execute(SCRIPT, Some(&request.to_json()?), options)
+    }
+}
+
+/// Default `ScriptBackend`, spawning an `osascript` process for each script.
+#[derive(Debug, Clone)]
+pub struct ScriptController {
+    osascript_path: PathBuf,
+}
+
+impl Default for ScriptController {
+    fn default() -> Self {
+        ScriptController {
+            osascript_path: PathBuf::from("osascript"),
+        }
+    }
+}
+
+impl ScriptController {
+    /// Sets the path of the `osascript` executable. Defaults to `osascript`, resolved through `PATH`.
+    pub fn osascript_path<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.osascript_path = path.as_ref().to_path_buf();
+        self
+    }
+
+    fn wait(&self, mut child: Child, options: &ExecutionOptions) -> Result<Output, Error> {
+        if !options.is_bounded() {
+            return Ok(child.wait_with_output()?);
+        }
+
+        let stdout = child.stdout.take().map(read_in_background);
+        let stderr = child.stderr.take().map(read_in_background);
+        let start = Instant::now();
 
-        return match serde_json::from_str::<T>(&output_str) {
-            Ok(data) => Ok(data),
-            Err(err) => {
-                error!("{:#?}", err);
-                Err(Error::DeserializationFailed)
+        let status = loop {
+            match child.try_wait() {
+                Ok(Some(status)) => break status,
+                Ok(None) => {
+                    if let Err(err) = options.check(start) {
+                        let _ = child.kill();
+                        let _ = child.wait();
+                        return Err(err);
+                    }
+                    thread::sleep(POLL_INTERVAL);
+                }
+                Err(err) => return Err(Error::Io(err)),
             }
         };
+
+        Ok(Output {
+            status,
+            stdout: stdout.and_then(|h| h.join().ok()).unwrap_or_default(),
+            stderr: stderr.and_then(|h| h.join().ok()).unwrap_or_default(),
+        })
     }
+}
+
+impl ScriptBackend for ScriptController {
+    fn execute(
+        &self,
+        script: &str,
+        params: Option<&str>,
+        options: &ExecutionOptions,
+    ) -> Result<String, Error> {
+        options.check(Instant::now())?;
 
-    pub fn execute(&self, command: &str, params: Option<String>) -> Result<Output, Error> {
-        let mut binding = Command::new("osascript");
-        let output = binding.arg("-l").arg("JavaScript").arg("-e").arg(command);
+        let mut command = Command::new(&self.osascript_path);
+        command
+            .arg("-l")
+            .arg("JavaScript")
+            .arg("-e")
+            .arg(script)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
 
         if let Some(params) = params {
-            output.arg(params);
+            command.arg(params);
         }
-        let data;
-        match output.output() {
-            Ok(d) => data = d,
-            Err(err) => {
-                error!("{:#?}", err);
-                return Err(Error::NoData);
-            }
+
+        let child = command.spawn()?;
+
+        let output = self.wait(child, options)?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let err = ScriptError::from_stderr(&stderr, output.status.code(), script, params);
+            return Err(err.into());
         }
 
-        return Ok(data);
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
     }
+}
+
+/// Delay between two checks of a running script.
+pub(crate) const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+fn read_in_background<R>(mut reader: R) -> thread::JoinHandle<Vec<u8>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        let _ = reader.read_to_end(&mut buffer);
+        buffer
+    })
+}
 
-    fn generate_json(&self, param_type: ParamType, id: Option<i32>, query: Option<&str>) -> String {
-        let mut hmap = HashMap::<&str, String>::new();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::os::unix::fs::PermissionsExt;
 
-        hmap.insert("param_type", param_type.to_string());
+    /// Stub `osascript` writing its pid next to itself, then hanging.
+    struct HangingStub(PathBuf);
 
-        if let Some(id) = id {
-            hmap.insert("id", id.to_string());
+    impl HangingStub {
+        fn new(name: &str) -> Self {
+            let path = std::env::temp_dir().join(format!(
+                "apple-music-osascript-{}-{}",
+                std::process::id(),
+                name
+            ));
+            fs::write(&path, "#!/bin/sh\necho $$ > \"$0.pid\"\nexec sleep 5\n").unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+            HangingStub(path)
         }
 
-        if let Some(query) = query {
-            hmap.insert("query", query.into());
+        fn controller(&self) -> ScriptController {
+            ScriptController::default().osascript_path(&self.0)
         }
-        return json!(hmap).to_string();
+
+        /// Returns true if the stub process is still running.
+        fn is_running(&self) -> bool {
+            let pid = fs::read_to_string(self.0.with_extension("pid")).unwrap();
+            Command::new("kill")
+                .arg("-0")
+                .arg(pid.trim())
+                .stderr(Stdio::null())
+                .status()
+                .unwrap()
+                .success()
+        }
+    }
+
+    impl Drop for HangingStub {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+            let _ = fs::remove_file(self.0.with_extension("pid"));
+        }
+    }
+
+    #[test]
+    fn kills_script_after_timeout() {
+        let stub = HangingStub::new("timeout");
+        let options = ExecutionOptions {
+            timeout: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+
+        let start = Instant::now();
+        assert!(matches!(
+            stub.controller().execute("script", None, &options),
+            Err(Error::Timeout(_))
+        ));
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(!stub.is_running());
+    }
+
+    #[test]
+    fn kills_script_once_cancelled() {
+        let stub = HangingStub::new("cancel");
+        let token = CancellationToken::new();
+        let options = ExecutionOptions {
+            cancellation: Some(token.clone()),
+            ..Default::default()
+        };
+
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(200));
+            token.cancel();
+        });
+
+        let start = Instant::now();
+        assert!(matches!(
+            stub.controller().execute("script", None, &options),
+            Err(Error::Cancelled)
+        ));
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(!stub.is_running());
+    }
+
+    #[test]
+    fn does_not_run_cancelled_scripts() {
+        let stub = HangingStub::new("cancelled");
+        let token = CancellationToken::new();
+        token.cancel();
+        let options = ExecutionOptions {
+            cancellation: Some(token),
+            ..Default::default()
+        };
+
+        assert!(matches!(
+            stub.controller().execute("script", None, &options),
+            Err(Error::Cancelled)
+        ));
+        assert!(!stub.0.with_extension("pid").exists());
     }
 }
@@ -1,37 +1,139 @@
+let Music;
+
 function run(input) {
-    let params = JSON.parse(input);
+    if (input[0] === "--session") {
+        return session();
+    }
+
+    return dispatch(JSON.parse(input[0]));
+}
+
+function dispatch(params) {
+    Music = Application(params['app'] || "Music");
 
     switch (params['param_type']) {
         case "artworks":
-            let track = Application('Music').tracks.byId(params["id"]);
+            let track = track_by(params);
             let extracted_artworks = extract_artworks(track);
             return JSON.stringify(extracted_artworks);
 
         case "allTracks":
-            return all_tracks();
+            return JSON.stringify(track_page(Music.tracks, params["fields"], params["offset"], params["limit"]));
 
         case "currentTrack":
-            let current_track = Application("Music").currentTrack().properties();
+            let current_track = track_properties(Music.currentTrack());
             return JSON.stringify(current_track);
 
         case "playlistById":
-            let playlist = Application("Music").playlists.byId(params["id"]);
+        case "playlistByPersistentId":
+            let playlist = playlist_by(params);
             let extracted_playlist = extract_playlist(playlist);
             return JSON.stringify(extracted_playlist);
 
         case "playlistTracks":
-            return playlist_tracks(params["id"]);
+            const playlist_tracks = playlist_by(params).tracks;
+            return JSON.stringify(track_page(playlist_tracks, params["fields"], params["offset"], params["limit"]));
+
+        case "filterTracks":
+            const matches = track_source(params);
+            return JSON.stringify(track_page(matches, params["fields"], params["offset"], params["limit"]));
+
+        case "filterPlaylists":
+            return JSON.stringify(playlist_source(params)().map(extract_playlist));
+
+        case "countTracks":
+            return JSON.stringify(track_source(params).length);
+
+        case "countPlaylists":
+            return JSON.stringify(playlist_source(params).length);
+
+        case "trackExists":
+            return JSON.stringify(track_by(params).exists());
+
+        case "playlistExists":
+            return JSON.stringify(playlist_by(params).exists());
+
+        case "trackById":
+        case "trackByPersistentId":
+            return JSON.stringify(track_properties(track_by(params)));
+
+        case "tracksByIds":
+            return JSON.stringify(tracks_by_ids(params));
 
         case "applicationData":
             return application_data();
 
         case "searchInPlaylist":
-            return search_in_playlist(params["id"], params["query"]);
+            return search(playlist_by(params), params["query"], params["kind"]);
+
+        case "search":
+            return search(search_target(params), params["query"], params["kind"]);
+
+        case "add":
+            return JSON.stringify(add(params));
+
+        case "fileTrackLocations":
+            return JSON.stringify(file_track_locations());
+    }
+}
+
+function session() {
+    ObjC.import('Foundation');
+
+    const stdin = $.NSFileHandle.fileHandleWithStandardInput;
+    const stdout = $.NSFileHandle.fileHandleWithStandardOutput;
+    const pending = $.NSMutableData.data;
+    let buffer = "";
+
+    while (true) {
+        const chunk = stdin.availableData;
+        if (chunk.length == 0) {
+            return;
+        }
+
+        pending.appendData(chunk);
+        const text = $.NSString.alloc.initWithDataEncoding(pending, $.NSUTF8StringEncoding);
+        if (text.isNil()) { /* wait for the rest of a multi-byte character */
+            continue;
+        }
+        pending.setLength(0);
+        buffer += text.js;
+
+        let index;
+        while ((index = buffer.indexOf("\n")) >= 0) {
+            const line = buffer.slice(0, index);
+            buffer = buffer.slice(index + 1);
+
+            if (line.trim().length > 0) {
+                const response = JSON.stringify(session_response(line)) + "\n";
+                stdout.writeData($(response).dataUsingEncoding($.NSUTF8StringEncoding));
+            }
+        }
+    }
+}
+
+function session_response(line) {
+    let message;
+    try {
+        message = JSON.parse(line);
+    } catch (e) {
+        return {id: null, error: {message: String(e.message || e)}};
+    }
+
+    try {
+        if (message.request) {
+            return {id: message.id, result: dispatch(message.request)};
+        }
+
+        const params = message.params;
+        const value = eval(message.script);
+        return {id: message.id, result: value === undefined ? "" : String(value)};
+    } catch (e) {
+        return {id: message.id, error: {message: String(e.message || e), code: e.errorNumber}};
     }
 }
 
 function application_data() {
-    const Music = Application("Music");
     const application = Music.properties();
 
     application.currentAirplayDevices = [];
@@ -54,7 +156,7 @@ function application_data() {
 
     Music.selection().forEach((track) => {
         try {
-            application.selection.push(track.properties());
+            application.selection.push(track_properties(track));
         } catch { /* continue loop */
         }
     });
@@ -75,13 +177,13 @@ function application_data() {
 }
 
 function extract_track(current_track) {
-    let track = current_track.properties();
+    let track = track_properties(current_track);
     try {
         let artworks = []
 
         current_track.artworks().forEach((artwork) => {
             let json = artwork.properties();
-            json.raw_data = artwork.rawData();
+            json.raw_data = artwork_base64(artwork);
             artworks.push(json)
         });
 
@@ -91,13 +193,42 @@ function extract_track(current_track) {
     return track;
 }
 
+function track_properties(track) {
+    let properties = track.properties();
+    properties.location = null;
+
+    if (properties.class === "fileTrack") {
+        try {
+            const location = track.location();
+            properties.location = location ? location.toString() : null;
+        } catch { /* file is missing */
+        }
+    }
+
+    return properties;
+}
+
+function file_track_locations() {
+    /* fetched for every file track at once, e.g. `fileTracks.location()` */
+    const tracks = Music.libraryPlaylists[0].fileTracks;
+    const persistent_ids = tracks.persistentID();
+    const names = tracks.name();
+    const locations = tracks.location();
+
+    return persistent_ids.map((persistent_id, index) => ({
+        persistent_id: persistent_id,
+        name: names[index],
+        location: locations[index] ? locations[index].toString() : null,
+    }));
+}
+
 function extract_artworks(current_track) {
     let artworks = [];
 
     current_track.artworks().forEach((artwork) => {
         try {
             let json = artwork.properties();
-            json.raw_data = artwork.rawData();
+            json.raw_data = artwork_base64(artwork);
             artworks.push(json);
         } catch { /* do nothing */
         }
@@ -106,6 +237,34 @@ function extract_artworks(current_track) {
     return artworks;
 }
 
+function artwork_base64(artwork) {
+    const data = artwork.rawData();
+
+    if (typeof data !== "string") {
+        return ObjC.wrap(data).base64EncodedStringWithOptions(0).js;
+    }
+
+    // AppleScript data literal, e.g. «data PNGf89504E47...»
+    const hex = data.replace(/^«data \w{4}/, "").replace(/»$/, "");
+    if (!/^([0-9A-Fa-f]{2})*$/.test(hex)) {
+        throw new Error("Unsupported artwork data");
+    }
+
+    const alphabet = "ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let base64 = "";
+
+    for (let i = 0; i < hex.length; i += 6) {
+        const chunk = hex.substring(i, i + 6);
+        const bits = parseInt(chunk.padEnd(6, "0"), 16);
+
+        for (let j = 0; j < 4; j++) {
+            base64 += j <= chunk.length / 2 ? alphabet[(bits >> (18 - 6 * j)) & 63] : "=";
+        }
+    }
+
+    return base64;
+}
+
 function extract_playlist(playlist) {
     let data = playlist.properties();
 
@@ -117,30 +276,111 @@ function extract_playlist(playlist) {
     return data;
 }
 
-function all_tracks() {
+function track_page(collection, fields, offset, limit) {
+    /* selected fields are fetched for the whole collection at once, e.g. `tracks.name()`: partial pages are split by the caller */
+    const values = fields ? fields.map((field) => collection[field]()) : null;
+    const total = values && values.length > 0 ? values[0].length : collection.length;
+    const start = Math.min(offset || 0, total);
+    const end = limit === undefined ? total : Math.min(start + limit, total);
+
     let tracks = []
-    Application("Music").tracks().forEach((track) => {
+    for (let index = start; index < end; index++) {
+        if (values) {
+            let track = {};
+            fields.forEach((field, position) => track[field] = values[position][index]);
+            tracks.push(track);
+            continue;
+        }
+
         try {
-            tracks.push(track.properties());
+            tracks.push(track_properties(collection[index]));
         } catch { /* continue loop */
         }
+    }
+
+    return {offset: start, end: end, total: total, tracks: tracks};
+}
+
+function by_persistent_id(collection, persistent_id) {
+    return collection.whose({persistentID: {_equals: persistent_id}})[0];
+}
+
+function track_by(params) {
+    if (params["persistent_id"] !== undefined) {
+        return by_persistent_id(Music.tracks, params["persistent_id"]);
+    }
+
+    return Music.tracks.byId(params["id"]);
+}
+
+function playlist_by(params) {
+    if (params["persistent_id"] !== undefined) {
+        return by_persistent_id(Music.playlists, params["persistent_id"]);
+    }
+
+    return Music.playlists.byId(params["id"]);
+}
+
+function tracks_by_ids(params) {
+    const persistent = params["persistent_ids"] !== undefined;
+    const ids = persistent ? params["persistent_ids"] : params["ids"];
+
+    return ids.map((id) => {
+        const track = persistent ? by_persistent_id(Music.tracks, id) : Music.tracks.byId(id);
+        try {
+            return track_properties(track);
+        } catch (e) {
+            if (e.errorNumber === -1728 || e.errorNumber === -1719) { /* track does not exist anymore */
+                return null;
+            }
+            throw e;
+        }
     });
+}
 
-    return JSON.stringify(tracks);
+function track_source(params) {
+    const scoped = params["id"] !== undefined || params["persistent_id"] !== undefined;
+    const source = scoped ? playlist_by(params).tracks : Music.tracks;
+    return params["filter"] === undefined ? source : source.whose(params["filter"]);
 }
 
-function search_in_playlist(id, query) {
-    let results = Application("Music").playlists.byId(id).search({for: query});
+function playlist_source(params) {
+    return params["filter"] === undefined ? Music.playlists : Music.playlists.whose(params["filter"]);
+}
 
-    let tracks = []
-    results.forEach((track) => tracks.push(track.properties()));
-    return JSON.stringify(tracks)
+function add(params) {
+    const files = params["paths"].map((path) => Path(path));
+    const scoped = params["id"] !== undefined || params["persistent_id"] !== undefined;
+    const added = Music.add(files, scoped ? {to: playlist_by(params)} : {});
+
+    if (added === null || added === undefined) {
+        return [];
+    }
+
+    return (Array.isArray(added) ? added : [added]).map(track_properties);
 }
 
-function playlist_tracks(id) {
-    const playlist = Application("Music").playlists.byId(id)
+function search_target(params) {
+    if (params["source"] !== undefined) {
+        return Music.sources.byName(params["source"]).libraryPlaylists[0];
+    }
+
+    if (params["id"] !== undefined || params["persistent_id"] !== undefined) {
+        return playlist_by(params);
+    }
+
+    return Music.libraryPlaylists[0];
+}
+
+function search(playlist, query, kind) {
+    let options = {for: query};
+    if (kind !== undefined) {
+        options.only = kind;
+    }
+
+    let results = playlist.search(options) || [];
 
     let tracks = []
-    playlist.tracks().forEach((track) => tracks.push(track.properties()));
-    return JSON.stringify(tracks);
+    results.forEach((track) => tracks.push(track_properties(track)));
+    return JSON.stringify(tracks)
 }
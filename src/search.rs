@@ -0,0 +1,63 @@
+use crate::handle::PlaylistRef;
+use crate::id::PlaylistId;
+use crate::playlist::Playlist;
+use crate::script_controller::ScriptRequest;
+use serde::Serialize;
+use strum_macros::Display;
+
+/// Kind of search run by Apple Music, as in the `only` parameter of its `search` command.
+#[derive(Serialize, Display, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum SearchKind {
+    Albums,
+    Artists,
+    Composers,
+    Displayed,
+    Songs,
+    #[default]
+    All,
+}
+
+/// Where Apple Music looks for Tracks when searching.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum SearchTarget {
+    /// The whole library
+    #[default]
+    Library,
+
+    /// A single Playlist
+    Playlist(PlaylistId),
+
+    /// The library of the source with the provided name, e.g. a shared library
+    Source(String),
+}
+
+impl SearchTarget {
+    /// Sets the targeted Playlist or source of the request, if any.
+    pub(crate) fn target(&self, request: ScriptRequest) -> ScriptRequest {
+        match self {
+            SearchTarget::Library => request,
+            SearchTarget::Playlist(playlist) => request.playlist(playlist),
+            SearchTarget::Source(name) => request.source(name),
+        }
+    }
+}
+
+impl From<PlaylistId> for SearchTarget {
+    fn from(playlist: PlaylistId) -> Self {
+        SearchTarget::Playlist(playlist)
+    }
+}
+
+impl From<&Playlist> for SearchTarget {
+    fn from(playlist: &Playlist) -> Self {
+        SearchTarget::Playlist(playlist.into())
+    }
+}
+
+impl<C> From<&PlaylistRef<C>> for SearchTarget {
+    fn from(playlist: &PlaylistRef<C>) -> Self {
+        SearchTarget::Playlist(playlist.into())
+    }
+}
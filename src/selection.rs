@@ -0,0 +1,189 @@
+use crate::batch::BatchFailure;
+use crate::calls::parse_output;
+use crate::error::Error;
+use crate::id::TrackId;
+use crate::jxa::Jxa;
+use serde::{de, Deserialize};
+
+/// Tracks targeted by a bulk operation run in a single script, e.g. by a `BulkTrackEditor`:
+/// either a list of Tracks, or every match of a `TrackQuery`.
+pub struct TrackSelection(Selection);
+
+enum Selection {
+    Tracks(Vec<(TrackId, Jxa)>),
+    Query(Jxa),
+}
+
+/// Result of a bulk operation on a single Track.
+#[derive(Debug)]
+pub struct TrackResult {
+    /// The targeted Track. Matches of a `TrackQuery` are identified by persistent ID
+    pub track: TrackId,
+
+    /// Whether the operation succeeded on the Track
+    pub result: Result<(), Error>,
+}
+
+#[derive(Deserialize)]
+struct SelectionOutput {
+    ids: Option<Vec<String>>,
+    failures: Vec<Option<BatchFailure>>,
+}
+
+impl TrackSelection {
+    /// Selects the provided Tracks, by id or persistent ID.
+    pub(crate) fn tracks<I, T>(app: &Jxa, tracks: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<TrackId>,
+    {
+        let tracks = tracks
+            .into_iter()
+            .map(|track| {
+                let id = track.into();
+                let specifier = id.specifier(app);
+                (id, specifier)
+            })
+            .collect();
+
+        TrackSelection(Selection::Tracks(tracks))
+    }
+
+    /// Selects every Track matched by the provided specifier, e.g. `TrackQuery::specifier`.
+    pub(crate) fn query(matches: Jxa) -> Self {
+        TrackSelection(Selection::Query(matches))
+    }
+
+    /// Returns true if the selection is an empty list, which does not need any script.
+    pub(crate) fn is_empty(&self) -> bool {
+        matches!(&self.0, Selection::Tracks(tracks) if tracks.is_empty())
+    }
+
+    /// Returns the script running the statements on every selected Track, available as `track`.
+    /// A failing Track does not prevent the statements from running on the others.
+    pub(crate) fn script(&self, statements: &[String]) -> String {
+        let (tracks, ids) = match &self.0 {
+            Selection::Tracks(tracks) => {
+                let tracks = tracks
+                    .iter()
+                    .map(|(_, track)| track.as_str())
+                    .collect::<Vec<_>>();
+                (format!("[{}]", tracks.join(", ")), "null")
+            }
+            Selection::Query(matches) => (
+                format!("{}()", matches),
+                "tracks.map((track) => track.persistentID())",
+            ),
+        };
+
+        let statements: String = statements
+            .iter()
+            .map(|statement| format!("            {}\n", statement))
+            .collect();
+
+        format!(
+            "(() => {{\n    const tracks = {};\n    const ids = {};\n    const failures = tracks.map((track) => {{\n        try {{\n{}            return null;\n        }} catch (e) {{\n            return {{message: String(e.message || e), code: e.errorNumber === undefined ? null : e.errorNumber, kind: e.kind}};\n        }}\n    }});\n    return JSON.stringify({{ids: ids, failures: failures}});\n}})()",
+            tracks, ids, statements
+        )
+    }
+
+    /// Returns the result of every selected Track, from the output of its script.
+    pub(crate) fn results(self, script: &str, output: &str) -> Result<Vec<TrackResult>, Error> {
+        let output: SelectionOutput = parse_output(output)?;
+
+        let tracks: Vec<TrackId> = match (self.0, output.ids) {
+            (Selection::Tracks(tracks), _) => tracks.into_iter().map(|(id, _)| id).collect(),
+            (Selection::Query(_), Some(ids)) => {
+                ids.into_iter().map(TrackId::PersistentId).collect()
+            }
+            (Selection::Query(_), None) => Vec::new(),
+        };
+
+        if tracks.len() != output.failures.len() {
+            return Err(Error::Deserialization {
+                path: String::from("failures"),
+                source: de::Error::invalid_length(output.failures.len(), &"one result per track"),
+            });
+        }
+
+        Ok(tracks
+            .into_iter()
+            .zip(output.failures)
+            .map(|(track, failure)| TrackResult {
+                track,
+                result: match failure {
+                    None => Ok(()),
+                    Some(failure) => Err(failure.into_error(script)),
+                },
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::track::TrackKind;
+
+    fn tracks() -> TrackSelection {
+        TrackSelection::tracks(&Jxa::application("Music"), [1, 2, 3])
+    }
+
+    #[test]
+    fn maps_failures_to_their_track() {
+        let output = r#"{"ids": null, "failures": [null, {"message": "Can't set rating.", "code": -10006}, {"message": "Only file tracks can be refreshed", "code": null, "kind": "sharedTrack"}]}"#;
+        let results = tracks().results("script", output).unwrap();
+
+        let ids: Vec<_> = results.iter().map(|result| result.track.clone()).collect();
+        assert_eq!(ids, [TrackId::Id(1), TrackId::Id(2), TrackId::Id(3)]);
+
+        assert!(results[0].result.is_ok());
+        match &results[1].result {
+            Err(Error::ScriptFailed(err)) => {
+                assert_eq!(err.code, Some(-10006));
+                assert_eq!(err.script, "script");
+            }
+            result => panic!("expected ScriptFailed, got {:?}", result),
+        }
+        assert!(matches!(
+            results[2].result,
+            Err(Error::NotFileTrack(TrackKind::SharedTrack))
+        ));
+    }
+
+    #[test]
+    fn identifies_query_matches_by_persistent_id() {
+        let selection = TrackSelection::query(Jxa::raw("matches"));
+        let output = r#"{"ids": ["ABC", "DEF"], "failures": [null, null]}"#;
+        let results = selection.results("script", output).unwrap();
+
+        assert_eq!(results[0].track, TrackId::persistent("ABC"));
+        assert_eq!(results[1].track, TrackId::persistent("DEF"));
+        assert!(results.iter().all(|result| result.result.is_ok()));
+    }
+
+    #[test]
+    fn rejects_results_not_matching_the_tracks() {
+        let output = r#"{"ids": null, "failures": [null, null]}"#;
+        assert!(matches!(
+            tracks().results("script", output),
+            Err(Error::Deserialization { .. })
+        ));
+
+        let output = r#"{"ids": ["ABC"], "failures": [null, null]}"#;
+        assert!(matches!(
+            TrackSelection::query(Jxa::raw("matches")).results("script", output),
+            Err(Error::Deserialization { .. })
+        ));
+    }
+
+    #[test]
+    fn returns_nothing_for_queries_without_match() {
+        let output = r#"{"ids": [], "failures": []}"#;
+        let results = TrackSelection::query(Jxa::raw("matches"))
+            .results("script", output)
+            .unwrap();
+
+        assert!(results.is_empty());
+    }
+}
@@ -0,0 +1,521 @@
+use crate::error::{Error, ScriptError};
+use crate::script_controller::{
+    ExecutionOptions, ScriptBackend, ScriptRequest, POLL_INTERVAL, SCRIPT,
+};
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::{BufRead, BufReader, ErrorKind, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Mutex, MutexGuard, TryLockError};
+use std::thread::{self, JoinHandle};
+use std::time::Instant;
+
+/// `ScriptBackend` keeping a single long-lived interpreter process alive.
+///
+/// Instead of spawning one `osascript` process per call, every script is sent to the same process
+/// as a newline-delimited JSON message on its stdin, and answered on its stdout:
+/// - Typed request: `{"id": 1, "request": {"param_type": "currentTrack"}}`
+/// - Raw script: `{"id": 2, "script": "Application('Music').play()", "params": null}`
+/// - Success: `{"id": 1, "result": "<script output>"}`
+/// - Failure: `{"id": 2, "error": {"message": "Can't get object.", "code": -1728}}`
+///
+/// The process is spawned on first use and automatically restarted if it crashes,
+/// or if it is killed because a script timed out or was cancelled.
+/// A script is never run twice: it is only sent again to a new process if it could not be written
+/// to the previous one. A crash while running it is returned, built from the stderr output of the process.
+/// Scripts of concurrent calls run one at a time: a call waits for its turn within its own timeout or cancellation.
+pub struct ScriptSession {
+    spawner: Box<dyn Fn() -> Command + Send + Sync>,
+    process: Mutex<Option<SessionProcess>>,
+    next_id: AtomicU64,
+}
+
+struct SessionProcess {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    stderr: Option<JoinHandle<String>>,
+}
+
+#[derive(Serialize)]
+struct SessionMessage<'a> {
+    id: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    request: Option<&'a ScriptRequest>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    script: Option<&'a str>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    params: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct SessionResponse {
+    id: Option<u64>,
+    result: Option<String>,
+    error: Option<SessionError>,
+}
+
+#[derive(Deserialize)]
+struct SessionError {
+    message: String,
+    code: Option<i32>,
+}
+
+/// Number of stderr lines kept to explain a crash of the interpreter.
+const STDERR_LINES: usize = 16;
+
+impl Default for ScriptSession {
+    fn default() -> Self {
+        ScriptSession::with_osascript_path("osascript")
+    }
+}
+
+impl ScriptSession {
+    /// Creates a session running the embedded script through the provided `osascript` executable.
+    pub fn with_osascript_path<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref().to_path_buf();
+
+        ScriptSession::with_command(move || {
+            let mut command = Command::new(&path);
+            command
+                .arg("-l")
+                .arg("JavaScript")
+                .arg("-e")
+                .arg(SCRIPT)
+                .arg("--session");
+            command
+        })
+    }
+
+    /// Creates a session spawning its interpreter with the provided factory.
+    /// The spawned process must implement the protocol described on `ScriptSession`.
+    pub fn with_command<F>(spawner: F) -> Self
+    where
+        F: Fn() -> Command + Send + Sync + 'static,
+    {
+        ScriptSession {
+            spawner: Box::new(spawner),
+            process: Mutex::new(None),
+            next_id: AtomicU64::new(1),
+        }
+    }
+
+    /// Kills the interpreter process, if running. A new one is spawned on next call.
+    pub fn shutdown(&self) {
+        if let Some(mut process) = self.lock().take() {
+            process.kill();
+        }
+    }
+
+    fn send(
+        &self,
+        message: &SessionMessage,
+        script: &str,
+        params: Option<&str>,
+        options: &ExecutionOptions,
+    ) -> Result<String, Error> {
+        let start = Instant::now();
+        options.check(start)?;
+
+        let mut line = serde_json::to_string(message).map_err(Error::Serialization)?;
+        line.push('\n');
+
+        let mut process = self.acquire(options, start)?;
+        if process.as_mut().is_some_and(SessionProcess::has_exited) {
+            warn!("Script session exited, restarting it");
+            if let Some(mut exited) = process.take() {
+                exited.kill();
+            }
+        }
+
+        let mut running = match process.take() {
+            Some(running) => running,
+            None => self.spawn()?,
+        };
+
+        if let Err(err) = running.write(&line) {
+            // The message never reached the crashed process: it can safely be sent to a new one.
+            warn!("Script session crashed, restarting it: {}", err);
+            running.kill();
+            running = self.spawn()?;
+
+            if let Err(err) = running.write(&line) {
+                return Err(running.crashed(err, script, params));
+            }
+        }
+
+        let response = match running.read(message.id, options, start) {
+            Ok(response) => response,
+            // The message may have been run before the crash: it is not sent again.
+            Err(Error::Io(err)) => return Err(running.crashed(err, script, params)),
+            Err(err) => {
+                // The interpreter is still busy with the aborted script: it cannot be reused.
+                running.kill();
+                return Err(err);
+            }
+        };
+        *process = Some(running);
+
+        match (response.result, response.error) {
+            (_, Some(err)) => Err(ScriptError {
+                code: err.code,
+                message: err.message,
+                exit_code: None,
+                script: script.into(),
+                params: params.map(String::from),
+            }
+            .into()),
+            (Some(result), None) => Ok(result),
+            (None, None) => Ok(String::new()),
+        }
+    }
+
+    fn spawn(&self) -> Result<SessionProcess, Error> {
+        let mut child = (self.spawner)()
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            let _ = child.kill();
+            return Err(Error::Io(ErrorKind::BrokenPipe.into()));
+        };
+
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        // Only the last lines are kept, to explain a crash.
+        let stderr = child.stderr.take().map(|stderr| {
+            thread::spawn(move || {
+                let mut lines = VecDeque::new();
+                for line in BufReader::new(stderr).lines() {
+                    let Ok(line) = line else { break };
+                    if lines.len() == STDERR_LINES {
+                        lines.pop_front();
+                    }
+                    lines.push_back(line);
+                }
+                Vec::from(lines).join("\n")
+            })
+        });
+
+        Ok(SessionProcess {
+            child,
+            stdin,
+            lines,
+            stderr,
+        })
+    }
+
+    /// Waits for the interpreter to be done with the scripts of other threads, for as long as the options allow.
+    fn acquire(
+        &self,
+        options: &ExecutionOptions,
+        start: Instant,
+    ) -> Result<MutexGuard<'_, Option<SessionProcess>>, Error> {
+        if !options.is_bounded() {
+            return Ok(self.lock());
+        }
+
+        loop {
+            match self.process.try_lock() {
+                Ok(process) => return Ok(process),
+                Err(TryLockError::Poisoned(poisoned)) => return Ok(poisoned.into_inner()),
+                Err(TryLockError::WouldBlock) => {
+                    options.check(start)?;
+                    thread::sleep(POLL_INTERVAL);
+                }
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Option<SessionProcess>> {
+        self.process
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl SessionProcess {
+    fn write(&mut self, line: &str) -> std::io::Result<()> {
+        self.stdin.write_all(line.as_bytes())?;
+        self.stdin.flush()
+    }
+
+    fn read(
+        &mut self,
+        id: u64,
+        options: &ExecutionOptions,
+        start: Instant,
+    ) -> Result<SessionResponse, Error> {
+        loop {
+            let line = match self.lines.recv_timeout(POLL_INTERVAL) {
+                Ok(line) => line,
+                Err(RecvTimeoutError::Timeout) => {
+                    options.check(start)?;
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    return Err(Error::Io(ErrorKind::UnexpectedEof.into()));
+                }
+            };
+
+            let response = match serde_json::from_str::<SessionResponse>(&line) {
+                Ok(response) => response,
+                Err(err) => {
+                    warn!("Skipping unexpected script session output: {}", err);
+                    continue;
+                }
+            };
+
+            // Responses to previously abandoned messages are skipped.
+            if response.id.is_none() || response.id == Some(id) {
+                return Ok(response);
+            }
+        }
+    }
+
+    fn has_exited(&mut self) -> bool {
+        matches!(self.child.try_wait(), Ok(Some(_)))
+    }
+
+    /// Returns the error explaining the crash of the process: a `ScriptError` built from its stderr output
+    /// if any, e.g. `Error::NotRunning`, or the provided I/O error.
+    fn crashed(mut self, err: std::io::Error, script: &str, params: Option<&str>) -> Error {
+        let _ = self.child.kill();
+        let exit_code = self.child.wait().ok().and_then(|status| status.code());
+        let stderr = self
+            .stderr
+            .take()
+            .and_then(|stderr| stderr.join().ok())
+            .unwrap_or_default();
+
+        if stderr.trim().is_empty() {
+            return Error::Io(err);
+        }
+
+        ScriptError::from_stderr(&stderr, exit_code, script, params).into()
+    }
+
+    fn kill(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for ScriptSession {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl ScriptBackend for ScriptSession {
+    fn execute(
+        &self,
+        script: &str,
+        params: Option<&str>,
+        options: &ExecutionOptions,
+    ) -> Result<String, Error> {
+        let message = SessionMessage {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            request: None,
+            script: Some(script),
+            params,
+        };
+
+        self.send(&message, script, params, options)
+    }
+
+    fn execute_request(
+        &self,
+        request: &ScriptRequest,
+        options: &ExecutionOptions,
+    ) -> Result<String, Error> {
+        let message = SessionMessage {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            request: Some(request),
+            script: None,
+            params: None,
+        };
+
+        let params = request.to_json()?;
+        self.send(&message, SCRIPT, Some(&params), options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::script_controller::CancellationToken;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    /// Stub interpreter answering the raw script of every message, one line at a time.
+    const STUB: &str = r#"
+while IFS= read -r line; do
+    id=$(printf '%s' "$line" | sed 's/^{"id":\([0-9]*\).*/\1/')
+    case "$line" in
+        *'"script":"ok"'*) printf '{"id":%s,"result":"done"}\n' "$id" ;;
+        *'"script":"pid"'*) printf '{"id":%s,"result":"%s"}\n' "$id" "$$" ;;
+        *'"script":"fail"'*) printf '{"id":%s,"error":{"message":"Invalid index.","code":-1719}}\n' "$id" ;;
+        *'"script":"hang"'*) sleep 5 ;;
+        *'"script":"crash"'*)
+            echo "execution error: Error: Error: Application isn't running. (-600)" >&2
+            exit 1 ;;
+    esac
+done
+"#;
+
+    fn stub() -> (ScriptSession, Arc<AtomicUsize>) {
+        let spawned = Arc::new(AtomicUsize::new(0));
+        let counter = spawned.clone();
+
+        let session = ScriptSession::with_command(move || {
+            counter.fetch_add(1, Ordering::Relaxed);
+            let mut command = Command::new("sh");
+            command.arg("-c").arg(STUB);
+            command
+        });
+
+        (session, spawned)
+    }
+
+    fn run(session: &ScriptSession, script: &str) -> Result<String, Error> {
+        session.execute(script, None, &ExecutionOptions::default())
+    }
+
+    #[test]
+    fn returns_result() {
+        let (session, spawned) = stub();
+
+        assert_eq!(run(&session, "ok").unwrap(), "done");
+        assert_eq!(run(&session, "ok").unwrap(), "done");
+        assert_eq!(spawned.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn returns_script_error() {
+        let (session, spawned) = stub();
+
+        match run(&session, "fail") {
+            Err(Error::ObjectNotFound(err)) => {
+                assert_eq!(err.code, Some(ScriptError::INVALID_INDEX));
+                assert_eq!(err.message, "Invalid index.");
+                assert_eq!(err.script, "fail");
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+
+        // A failing script does not end the session.
+        assert_eq!(run(&session, "ok").unwrap(), "done");
+        assert_eq!(spawned.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn restarts_after_timeout() {
+        let (session, spawned) = stub();
+        let pid = run(&session, "pid").unwrap();
+
+        let options = ExecutionOptions {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            session.execute("hang", None, &options),
+            Err(Error::Timeout(_))
+        ));
+
+        assert_ne!(run(&session, "pid").unwrap(), pid);
+        assert_eq!(spawned.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn stops_waiting_for_a_busy_session() {
+        let (session, _) = stub();
+        let session = Arc::new(session);
+
+        let busy = session.clone();
+        let hanging = thread::spawn(move || {
+            let options = ExecutionOptions {
+                timeout: Some(Duration::from_secs(1)),
+                ..Default::default()
+            };
+            busy.execute("hang", None, &options)
+        });
+        thread::sleep(Duration::from_millis(100));
+
+        let start = Instant::now();
+        let options = ExecutionOptions {
+            timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+        assert!(matches!(
+            session.execute("ok", None, &options),
+            Err(Error::Timeout(_))
+        ));
+
+        let token = CancellationToken::new();
+        let cancel = token.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            cancel.cancel();
+        });
+        let options = ExecutionOptions {
+            cancellation: Some(token),
+            ..Default::default()
+        };
+        assert!(matches!(
+            session.execute("ok", None, &options),
+            Err(Error::Cancelled)
+        ));
+        assert!(start.elapsed() < Duration::from_millis(800));
+
+        assert!(matches!(hanging.join().unwrap(), Err(Error::Timeout(_))));
+        assert_eq!(run(&session, "ok").unwrap(), "done");
+    }
+
+    #[test]
+    fn returns_crash_without_retrying() {
+        let (session, spawned) = stub();
+
+        match run(&session, "crash") {
+            Err(Error::NotRunning(err)) => {
+                assert_eq!(err.message, "Application isn't running.");
+                assert_eq!(err.exit_code, Some(1));
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+        assert_eq!(spawned.load(Ordering::Relaxed), 1);
+
+        assert_eq!(run(&session, "ok").unwrap(), "done");
+        assert_eq!(spawned.load(Ordering::Relaxed), 2);
+    }
+
+    #[test]
+    fn restarts_exited_process_before_sending() {
+        let (session, spawned) = stub();
+        let pid = run(&session, "pid").unwrap();
+
+        Command::new("kill").arg(&pid).status().unwrap();
+        thread::sleep(Duration::from_millis(100));
+
+        assert_eq!(run(&session, "ok").unwrap(), "done");
+        assert_eq!(spawned.load(Ordering::Relaxed), 2);
+    }
+}
@@ -1,7 +1,12 @@
+use crate::album::Album;
+use crate::artwork::ArtworkImage;
+use crate::client::AppleMusicClient;
+use crate::editor::TrackEditor;
 use crate::error::Error;
-use crate::script_controller::{ParamType, ScriptController};
+use crate::id::TrackId;
+use log::error;
 use serde::Deserialize;
-use std::io::Read;
+use std::path::{Path, PathBuf};
 use urlencoding::encode;
 
 /// Provides data related to a specific Track as well as its artworks.
@@ -46,7 +51,7 @@ pub struct Track {
     pub artworks_raw_data: Option<Vec<Artwork>>,
 
     /// The URL of the main artwork for this track.
-    artwork_url: Option<String>,
+    pub(crate) artwork_url: Option<String>,
 
     /// The artist of the track
     pub artist: String,
@@ -55,7 +60,7 @@ pub struct Track {
     pub bit_rate: Option<i16>,
 
     /// The bookmark time of the track in seconds
-    pub bookmark: i8,
+    pub bookmark: f64,
 
     /// Is the playback position for this track remembered?
     pub bookmarkable: bool,
@@ -88,6 +93,12 @@ pub struct Track {
     /// The description of the track
     pub description: String,
 
+    /// The total number of discs in the source album
+    pub disc_count: Option<i16>,
+
+    /// The index of the disc containing this track on the source album
+    pub disc_number: Option<i16>,
+
     /// Is this track disliked?
     pub disliked: bool,
 
@@ -129,6 +140,9 @@ pub struct Track {
     /// A text description of the track
     pub kind: Option<String>,
 
+    /// The location of the file of the track. Only set for file tracks whose file can be found by Apple Music
+    pub location: Option<PathBuf>,
+
     /// The long description of the track
     pub long_description: Option<String>,
 
@@ -245,134 +259,139 @@ pub struct Track {
 impl Track {
     /// Method that either returns an already fetched artwork_url, or fetches it and then returns it.
     pub fn artwork_url(&mut self) -> &Option<String> {
-        if self.artwork_url == None {
-            self.fetch_itunes_store_data()
+        if self.artwork_url.is_none() {
+            if let Err(err) = self.fetch_itunes_store_data() {
+                error!("{}", err);
+            }
         }
 
-        return &self.artwork_url;
+        &self.artwork_url
     }
 
     /// Method that either returns an already fetched track_url, or fetches it and then returns it.
     pub fn track_url(&mut self) -> &Option<String> {
-        if self.track_url == None {
-            self.fetch_itunes_store_data()
+        if self.track_url.is_none() {
+            if let Err(err) = self.fetch_itunes_store_data() {
+                error!("{}", err);
+            }
         }
 
-        return &self.track_url;
+        &self.track_url
     }
 
     /// Returns a list of all artworks with their raw_data.
     /// Recommended to use Track.get_artwork_url() instead.
     pub fn fetch_artworks_raw_data(&mut self) -> Result<(), Error> {
-        match ScriptController.execute_script::<Vec<Artwork>>(
-            ParamType::Artworks,
-            Some(self.id),
-            None,
-        ) {
-            Ok(data) => {
-                self.artworks_raw_data = Some(data);
-                Ok(())
-            }
-            Err(err) => Err(err),
+        self.artworks_raw_data = Some(AppleMusicClient::default().fetch_track_artworks(&*self)?);
+        Ok(())
+    }
+
+    /// Adds the provided image as the last artwork of the Track.
+    pub fn add_artwork(&self, image: &ArtworkImage) -> Result<(), Error> {
+        AppleMusicClient::default().add_track_artwork(self, image)
+    }
+
+    /// Replaces the first artwork of the Track with the provided image, or adds it if there is none.
+    pub fn replace_artwork(&self, image: &ArtworkImage) -> Result<(), Error> {
+        AppleMusicClient::default().replace_track_artwork(self, image)
+    }
+
+    /// Deletes every artwork of the Track.
+    pub fn remove_artworks(&self) -> Result<(), Error> {
+        AppleMusicClient::default().remove_track_artworks(self)
+    }
+
+    /// Updates the Track from the metadata of its file, e.g. after its tags were rewritten on disk.
+    /// Returns an `Error::NotFileTrack` if the Track is not a file Track, without running any script.
+    pub fn refresh(&self) -> Result<(), Error> {
+        if self.class != TrackKind::FileTrack {
+            return Err(Error::NotFileTrack(self.class));
         }
+
+        AppleMusicClient::default().refresh_track(self)
     }
 
-    /// Reveals and selects Track in Apple Music.
-    pub fn reveal_in_player(&self) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').reveal(Application('Music').tracks.byId({}))",
-            self.id
-        );
+    /// Returns a new TrackEditor, collecting changes to the Track to apply them in a single script.
+    pub fn edit(&self) -> TrackEditor {
+        AppleMusicClient::default().edit_track(self)
+    }
 
-        let _ = ScriptController.execute(cmd.as_str(), None);
+    /// Returns the Album of the Track, from its album name & album artist.
+    pub fn get_album(&self) -> Album {
+        AppleMusicClient::default().album(&self.album, &self.album_artist)
+    }
 
-        Ok(())
+    /// Reveals and selects Track in Apple Music.
+    pub fn reveal_in_player(&self) -> Result<(), Error> {
+        AppleMusicClient::default().reveal_track(self)
     }
 
     /// Triggers a download on Apple Music Player for the Track.
     pub fn download(&self) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').download(Application('Music').tracks.byId({}))",
-            self.id
-        );
-
-        let _ = ScriptController.execute(cmd.as_str(), None);
-
-        Ok(())
+        AppleMusicClient::default().download_track(self)
     }
 
     /// Favorites / "Unfavorites" a Track.
     pub fn set_favorited(&self, value: bool) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').tracks.byId({}).favorited = {}",
-            self.id, value
-        );
-
-        let _ = ScriptController.execute(cmd.as_str(), None);
-
-        Ok(())
+        AppleMusicClient::default().set_track_favorited(self, value)
     }
 
     /// Dislikes / "Undislikes" a Track.
     pub fn set_disliked(&self, value: bool) -> Result<(), Error> {
-        let cmd = format!(
-            "Application('Music').tracks.byId({}).disliked = {}",
-            self.id, value
-        );
+        AppleMusicClient::default().set_track_disliked(self, value)
+    }
 
-        let _ = ScriptController.execute(cmd.as_str(), None);
+    /// Search for a song in the Itunes Store and extract its artwork_url & track_url.
+    pub fn fetch_itunes_store_data(&mut self) -> Result<(), Error> {
+        for request in self.itunes_store_requests() {
+            let body = reqwest::blocking::get(request)?
+                .error_for_status()?
+                .text()?;
+            self.apply_itunes_store_search(&body);
+
+            if self.artwork_url.is_some() {
+                break;
+            }
+        }
 
         Ok(())
     }
 
-    /// Search for a song in the Itunes Store and extract its artwork_url & track_url.
-    fn fetch_itunes_store_data(&mut self) {
-        let request = format!(
-            "https://itunes.apple.com/search?term={}&entity=song&limit=200",
-            encode(self.name.as_str())
-        );
-        self.fetch_itunes_store_by_request(request);
-
-        if self.artwork_url == None {
-            let request = format!(
+    /// Returns the Itunes Store searches to run, by priority: by name, by album, then by artist.
+    pub(crate) fn itunes_store_requests(&self) -> [String; 3] {
+        [
+            format!(
+                "https://itunes.apple.com/search?term={}&entity=song&limit=200",
+                encode(self.name.as_str())
+            ),
+            format!(
                 "https://itunes.apple.com/search?term={}&entity=song&attribute=albumTerm&limit=200",
                 encode(self.album.as_str())
-            );
-            self.fetch_itunes_store_by_request(request);
-        }
-
-        if self.artwork_url == None {
-            let request = format!(
+            ),
+            format!(
                 "https://itunes.apple.com/search?term={}&entity=song&limit=200",
                 encode(self.artist.as_str())
-            );
-            self.fetch_itunes_store_by_request(request);
-        }
+            ),
+        ]
     }
 
-    fn fetch_itunes_store_by_request(&mut self, request: String) {
-        let mut res = reqwest::blocking::get(request).unwrap();
-        let mut body = String::new();
-        res.read_to_string(&mut body).unwrap();
-
-        if let Ok(search) = serde_json::from_str::<ITunesStoreSearch>(body.as_str()) {
+    /// Extracts artwork_url & track_url from an Itunes Store search response, if it matches the Track.
+    pub(crate) fn apply_itunes_store_search(&mut self, body: &str) {
+        if let Ok(search) = serde_json::from_str::<ITunesStoreSearch>(body) {
             if search.result_count == 1 {
                 self.artwork_url = Some(search.results[0].clone().artwork_url_100);
                 self.track_url = Some(search.results[0].clone().track_view_url);
             } else {
                 let result = search.results.iter().find(|result| {
-                    (&result.track_name.to_lowercase() == &self.name.to_lowercase()
-                        || &result.track_censored_name.to_lowercase() == &self.name.to_lowercase())
-                        && (&result.artist_name.to_lowercase() == &self.artist.to_lowercase()
-                            || &result.collection_name.to_lowercase() == &self.album.to_lowercase())
+                    (result.track_name.to_lowercase() == self.name.to_lowercase()
+                        || result.track_censored_name.to_lowercase() == self.name.to_lowercase())
+                        && (result.artist_name.to_lowercase() == self.artist.to_lowercase()
+                            || result.collection_name.to_lowercase() == self.album.to_lowercase())
                 });
 
-                match result {
-                    Some(data) => {
-                        self.artwork_url = Some(data.clone().artwork_url_100);
-                        self.track_url = Some(data.clone().track_view_url);
-                    }
-                    None => (),
+                if let Some(data) = result {
+                    self.artwork_url = Some(data.clone().artwork_url_100);
+                    self.track_url = Some(data.clone().track_view_url);
                 }
             }
         }
@@ -400,10 +419,36 @@ pub struct Artwork {
     /// Kind or purpose of this piece of artwork.
     pub kind: i32,
 
-    /// Data for this artwork, in original format.
+    /// Data for this artwork, in original format, base64-encoded. Decoded by `Artwork::bytes`.
     pub raw_data: String,
 }
 
+/// File Track whose file does not exist anymore, as reported by `AppleMusicClient::find_dead_tracks`.
+#[derive(Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DeadTrack {
+    /// The persistent ID of the Track
+    pub persistent_id: String,
+
+    /// The name of the Track
+    pub name: String,
+
+    /// The last known location of the file, if Apple Music still has one
+    pub location: Option<PathBuf>,
+}
+
+impl DeadTrack {
+    /// Returns true if the file of the Track cannot be found on disk.
+    pub(crate) fn is_dead(&self) -> bool {
+        !self.location.as_deref().is_some_and(Path::exists)
+    }
+}
+
+impl From<&DeadTrack> for TrackId {
+    fn from(track: &DeadTrack) -> Self {
+        TrackId::persistent(&track.persistent_id)
+    }
+}
+
 /// Struct representing a search through the Itunes Store.
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -473,11 +518,48 @@ pub enum MediaKind {
     Unknown,
 }
 
-/// Type of Track: From an URL, a File, or Shared.
-#[derive(Deserialize, Debug)]
+/// Type of Track: From an URL, a File, Shared, or any other class.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub enum TrackKind {
     SharedTrack,
     FileTrack,
     UrlTrack,
+
+    /// Any other class, e.g. `audioCDTrack` or a plain `track`
+    #[serde(other)]
+    Other,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    fn track(location: Option<PathBuf>) -> DeadTrack {
+        DeadTrack {
+            persistent_id: String::from("ABC"),
+            name: String::from("One More Time"),
+            location,
+        }
+    }
+
+    #[test]
+    fn is_alive_while_its_file_exists() {
+        let path = env::temp_dir().join(format!("apple-music-track-{}.mp3", std::process::id()));
+        fs::write(&path, b"").unwrap();
+        let track = track(Some(path.clone()));
+
+        assert!(!track.is_dead());
+
+        fs::remove_file(&path).unwrap();
+        assert!(track.is_dead());
+    }
+
+    #[test]
+    fn is_dead_without_file() {
+        assert!(track(None).is_dead());
+        assert!(track(Some(PathBuf::from("/nonexistent/apple-music/track.mp3"))).is_dead());
+    }
 }